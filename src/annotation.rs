@@ -8,6 +8,56 @@ use bstr::ByteSlice as _;
 /* project use */
 use crate::error;
 
+/// Feature types of transcript level annotation, gff `transcript` and Sequence Ontology
+/// transcript types used by Ensembl and RefSeq
+pub const TRANSCRIPT_FEATURES: &[&[u8]] = &[
+    b"transcript",
+    b"mRNA",
+    b"lnc_RNA",
+    b"lncRNA",
+    b"ncRNA",
+    b"snRNA",
+    b"snoRNA",
+    b"miRNA",
+    b"rRNA",
+    b"tRNA",
+    b"scRNA",
+    b"scaRNA",
+    b"piRNA",
+    b"guide_RNA",
+    b"antisense_RNA",
+    b"RNase_MRP_RNA",
+    b"RNase_P_RNA",
+    b"SRP_RNA",
+    b"telomerase_RNA",
+    b"vault_RNA",
+    b"Y_RNA",
+    b"primary_transcript",
+    b"nc_primary_transcript",
+    b"pseudogenic_transcript",
+    b"unconfirmed_transcript",
+    b"processed_transcript",
+    b"NMD_transcript_variant",
+    b"aberrant_processed_transcript",
+    b"C_gene_segment",
+    b"D_gene_segment",
+    b"J_gene_segment",
+    b"V_gene_segment",
+];
+
+/// Feature types of gene level annotation
+pub const GENE_FEATURES: &[&[u8]] = &[b"gene", b"ncRNA_gene", b"pseudogene"];
+
+/// Feature is a transcript level annotation
+pub fn is_transcript(feature: &[u8]) -> bool {
+    TRANSCRIPT_FEATURES.contains(&feature)
+}
+
+/// Feature is a gene level annotation
+pub fn is_gene(feature: &[u8]) -> bool {
+    GENE_FEATURES.contains(&feature)
+}
+
 /// Define annotation strand
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Strand {
@@ -39,6 +89,17 @@ pub enum Frame {
     Two,
 }
 
+impl Frame {
+    /// Number of bases before first complete codon, 0 if frame is unknow
+    pub fn phase(&self) -> u64 {
+        match self {
+            Frame::Unknow | Frame::Zero => 0,
+            Frame::One => 1,
+            Frame::Two => 2,
+        }
+    }
+}
+
 impl std::fmt::Display for Frame {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         match self {
@@ -53,13 +114,60 @@ impl std::fmt::Display for Frame {
 /// Store attribute of gff record
 #[derive(Debug, Clone, std::default::Default, PartialEq, Eq)]
 pub struct Attribute {
-    pub(crate) id: Vec<u8>,     // ID=
-    pub(crate) name: Vec<u8>,   // Name=
-    pub(crate) parent: Vec<u8>, // Parent=
+    pub(crate) id: Vec<u8>,                         // ID=
+    pub(crate) name: Vec<u8>,                       // Name=
+    pub(crate) parents: Vec<Vec<u8>>,               // Parent=
+    pub(crate) attributes: Vec<(Vec<u8>, Vec<u8>)>, // other key=value
+}
+
+/// Decode %XX escaped character of gff3 attribute
+fn url_decode(value: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(value.len());
+
+    let mut index = 0;
+    while index < value.len() {
+        match (value[index], value.get(index + 1..index + 3)) {
+            (b'%', Some(hex)) => match std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(c) => {
+                    decoded.push(c);
+                    index += 3;
+                }
+                None => {
+                    decoded.push(b'%');
+                    index += 1;
+                }
+            },
+            (c, _) => {
+                decoded.push(c);
+                index += 1;
+            }
+        }
+    }
+
+    decoded
+}
+
+/// Escape character reserved in gff3 attribute
+fn url_encode(value: &[u8]) -> String {
+    let mut encoded = Vec::with_capacity(value.len());
+
+    for c in value {
+        match c {
+            b';' | b'=' | b'&' | b'%' | 0..=0x1f | 0x7f => {
+                encoded.extend(format!("%{:02X}", c).bytes())
+            }
+            _ => encoded.push(*c),
+        }
+    }
+
+    String::from_utf8_lossy(&encoded).into_owned()
 }
 
 impl Attribute {
-    /// Create an attribute from u8 slice
+    /// Create an attribute from u8 slice, values are url decoded
     pub fn from_u8_slice(slice: &[u8]) -> error::Result<Self> {
         let mut obj = Attribute::default();
 
@@ -69,18 +177,66 @@ impl Attribute {
 
         for attribute in slice.split_str(";") {
             match attribute {
-                [b'I', b'D', b'=', value @ ..] => obj.id = value.to_vec(),
-                [b'N', b'a', b'm', b'e', b'=', value @ ..] => obj.name = value.to_vec(),
+                [b'I', b'D', b'=', value @ ..] => obj.id = url_decode(value),
+                [b'N', b'a', b'm', b'e', b'=', value @ ..] => obj.name = url_decode(value),
                 [b'P', b'a', b'r', b'e', b'n', b't', b'=', value @ ..] => {
-                    obj.parent = value.to_vec()
+                    // split before decode, an encoded comma is part of parent id
+                    obj.parents = value
+                        .split_str(",")
+                        .filter(|parent| !parent.is_empty())
+                        .map(url_decode)
+                        .collect()
                 }
+                _ => {
+                    if let Some((key, value)) = attribute.split_once_str("=") {
+                        obj.push(url_decode(key.trim()), url_decode(value));
+                    }
+                }
+            }
+        }
+
+        Ok(obj)
+    }
+
+    /// Create an attribute from u8 slice of a gtf record, hierarchy is build from gene_id and transcript_id
+    pub fn from_gtf_u8_slice(slice: &[u8], feature: &[u8]) -> error::Result<Self> {
+        let mut obj = Attribute::default();
+
+        for attribute in slice.split_str(";") {
+            let Some((key, value)) = attribute.trim().split_once_str(" ") else {
+                continue;
+            };
+            let value = value.trim().trim_with(|c| c == '"');
+
+            match (feature, key) {
+                (b"gene", b"gene_id") => obj.id = value.to_vec(),
+                (b"gene", b"gene_name") => obj.name = value.to_vec(),
+                (b"transcript", b"transcript_id") => obj.id = value.to_vec(),
+                (b"transcript", b"transcript_name") => obj.name = value.to_vec(),
+                (b"transcript", b"gene_id") => obj.parents = vec![value.to_vec()],
+                (b"gene" | b"transcript", _) => {}
+                (b"exon", b"exon_id") => obj.id = value.to_vec(),
+                (_, b"transcript_id") => obj.parents = vec![value.to_vec()],
                 _ => {}
             }
+
+            obj.push(key.to_vec(), value.to_vec());
         }
 
         Ok(obj)
     }
 
+    /// Add an attribute, value of a key already present is append after a comma
+    fn push(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        match self.attributes.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => {
+                v.push(b',');
+                v.extend(value);
+            }
+            None => self.attributes.push((key, value)),
+        }
+    }
+
     /// Get gene name
     pub fn get_id(&self) -> &[u8] {
         &self.id
@@ -91,14 +247,55 @@ impl Attribute {
         &self.name
     }
 
-    /// Get exon_number
+    /// Get first parent
     pub fn get_parent(&self) -> &[u8] {
-        &self.parent
+        self.parents.first().map(Vec::as_slice).unwrap_or_default()
     }
 
-    /// Get exon_number
+    /// Replace all parents by value
     pub fn set_parent(&mut self, value: Vec<u8>) {
-        self.parent = value
+        self.parents = vec![value]
+    }
+
+    /// Get each parent, gff3 allow many parents separate by comma
+    pub fn get_parents(&self) -> impl std::iter::Iterator<Item = &[u8]> {
+        self.parents.iter().map(Vec::as_slice)
+    }
+
+    /// Get value associate to key, ID, Name and Parent are also accessible
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        match key {
+            b"ID" => Some(&self.id),
+            b"Name" => Some(&self.name),
+            b"Parent" => Some(self.get_parent()),
+            _ => self
+                .attributes
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_slice()),
+        }
+    }
+
+    /// Get all attributes except ID, Name and Parent
+    pub fn get_attributes(&self) -> &[(Vec<u8>, Vec<u8>)] {
+        &self.attributes
+    }
+
+    /// Remove all attributes except ID, Name and Parent
+    pub fn clear_attributes(&mut self) {
+        self.attributes = Vec::new();
+    }
+
+    /// Get tags
+    pub fn get_tags(&self) -> impl std::iter::Iterator<Item = &[u8]> {
+        self.get(b"tag")
+            .into_iter()
+            .flat_map(|value| value.split_str(","))
+    }
+
+    /// Attribute contains tag
+    pub fn has_tag(&self, tag: &[u8]) -> bool {
+        self.get_tags().any(|t| t == tag)
     }
 }
 
@@ -106,25 +303,22 @@ impl std::fmt::Display for Attribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         let mut fields = vec![];
 
-        unsafe {
-            if !self.id.is_empty() {
-                fields.push(format!(
-                    "ID={}",
-                    String::from_utf8_unchecked(self.id.to_vec())
-                ));
-            }
-            if !self.name.is_empty() {
-                fields.push(format!(
-                    "Name={}",
-                    String::from_utf8_unchecked(self.name.to_vec())
-                ));
-            }
-            if !self.parent.is_empty() {
-                fields.push(format!(
-                    "Parent={}",
-                    String::from_utf8_unchecked(self.parent.to_vec())
-                ));
-            }
+        if !self.id.is_empty() {
+            fields.push(format!("ID={}", url_encode(&self.id)));
+        }
+        if !self.name.is_empty() {
+            fields.push(format!("Name={}", url_encode(&self.name)));
+        }
+        if !self.parents.is_empty() {
+            let parents = self
+                .parents
+                .iter()
+                .map(|parent| url_encode(parent).replace(',', "%2C"))
+                .collect::<Vec<String>>();
+            fields.push(format!("Parent={}", parents.join(",")));
+        }
+        for (key, value) in &self.attributes {
+            fields.push(format!("{}={}", url_encode(key), url_encode(value)));
         }
 
         write!(f, "{}", fields.join(";"))
@@ -161,14 +355,40 @@ pub struct Annotation {
     pub(crate) attribute: Attribute,
 }
 
+/// Attribute column is in gtf format (`key "value";`) rather than gff3 format (`key=value;`)
+fn is_gtf(attribute: &[u8]) -> bool {
+    attribute
+        .trim_start()
+        .iter()
+        .find(|c| **c == b'=' || **c == b' ')
+        == Some(&b' ')
+}
+
 impl Annotation {
-    /// Build a new annotations from a csv::ByteRecord
+    /// Build a new annotations from a csv::ByteRecord, gff3 or gtf format is detected from attribute column
     pub fn from_byte_record(record: &csv::ByteRecord) -> error::Result<Self> {
+        let gtf = is_gtf(record.get(8).unwrap());
+        let feature = match (gtf, record.get(2).unwrap()) {
+            (true, b"five_prime_utr") => &b"five_prime_UTR"[..],
+            (true, b"three_prime_utr") => b"three_prime_UTR",
+            (_, feature) => feature,
+        };
+
+        let mut attribute = if gtf {
+            Attribute::from_gtf_u8_slice(record.get(8).unwrap(), feature)?
+        } else {
+            Attribute::from_u8_slice(record.get(8).unwrap())?
+        };
+        // Only transcript and gene attributes are used
+        if !is_transcript(feature) && !is_gene(feature) {
+            attribute.clear_attributes();
+        }
+
         unsafe {
             Ok(Self {
                 seqname: record.get(0).unwrap().to_vec(),
                 source: record.get(1).unwrap().to_vec(),
-                feature: record.get(2).unwrap().to_vec(),
+                feature: feature.to_vec(),
                 start: String::from_utf8_unchecked(record.get(3).unwrap().to_vec())
                     .parse::<u64>()
                     .unwrap(),
@@ -192,7 +412,7 @@ impl Annotation {
                     b"2" => Frame::Two,
                     _ => return Err(error::Error::GffBadFrame.into()),
                 },
-                attribute: Attribute::from_u8_slice(record.get(8).unwrap())?,
+                attribute,
             })
         }
     }
@@ -204,6 +424,7 @@ impl Annotation {
         obj.start = start;
         obj.stop = stop;
         obj.attribute.set_parent(a.attribute.get_id().to_vec());
+        obj.attribute.clear_attributes();
 
         obj
     }
@@ -248,6 +469,16 @@ impl Annotation {
         &self.frame
     }
 
+    /// Annotation is a transcript level annotation
+    pub fn is_transcript(&self) -> bool {
+        is_transcript(&self.feature)
+    }
+
+    /// Annotation is a gene level annotation
+    pub fn is_gene(&self) -> bool {
+        is_gene(&self.feature)
+    }
+
     /// Get attribute annotation
     pub fn get_attribute(&self) -> &Attribute {
         &self.attribute
@@ -258,6 +489,22 @@ impl Annotation {
         self.attribute.get_parent()
     }
 
+    /// Split annotation with many parents in one annotation by parent
+    pub fn split_by_parent(self) -> Vec<Self> {
+        if self.attribute.parents.len() < 2 {
+            return vec![self];
+        }
+
+        self.attribute
+            .get_parents()
+            .map(|parent| {
+                let mut obj = self.clone();
+                obj.attribute.set_parent(parent.to_vec());
+                obj
+            })
+            .collect()
+    }
+
     #[cfg(test)]
     /// Generate a fake annotation with a seqname, start and stop
     pub fn test_annotation(seqname: Vec<u8>, start: u64, stop: u64) -> Self {
@@ -331,9 +578,39 @@ mod tests {
 
         assert_eq!(
             format!("{}", attribute),
-            "ID=ENST00000797271.1;Name=transcript_name;Parent=ENSG00000286586.2"
+            "ID=ENST00000797271.1;Name=transcript_name;Parent=ENSG00000286586.2;gene_id=ENSG00000286586.2;transcript_id=ENST00000797271.1;gene_type=lncRNA;gene_name=ENSG00000286586;transcript_type=lncRNA;transcript_name=ENST00000797271;level=2;tag=basic,Ensembl_canonical,TAGENE;havana_gene=OTTHUMG00000194565.1"
+        );
+        assert_eq!(attribute.get(b"gene_type"), Some(&b"lncRNA"[..]));
+        assert_eq!(attribute.get(b"ID"), Some(&b"ENST00000797271.1"[..]));
+        assert_eq!(attribute.get(b"missing"), None);
+
+        let attribute = Attribute::from_u8_slice(b"ID=tr1;Parent=g1;tag=basic,Ensembl_canonical")?;
+        assert_eq!(
+            attribute.get_tags().collect::<Vec<&[u8]>>(),
+            vec![&b"basic"[..], &b"Ensembl_canonical"[..]]
+        );
+        assert!(attribute.has_tag(b"Ensembl_canonical"));
+        assert!(!attribute.has_tag(b"MANE_Select"));
+        assert_eq!(
+            format!("{}", attribute),
+            "ID=tr1;Parent=g1;tag=basic,Ensembl_canonical"
+        );
+
+        let attribute =
+            Attribute::from_u8_slice(b"ID=tr%3B1;Parent=g1;note=a%3Db%2Cc%25 d;Dbxref=x%26y")?;
+        assert_eq!(attribute.get_id(), b"tr;1");
+        assert_eq!(attribute.get(b"note"), Some(&b"a=b,c% d"[..]));
+        assert_eq!(attribute.get(b"Dbxref"), Some(&b"x&y"[..]));
+        assert_eq!(
+            format!("{}", attribute),
+            "ID=tr%3B1;Parent=g1;note=a%3Db,c%25 d;Dbxref=x%26y"
         );
 
+        // Invalid escape, not utf8 or not hexadecimal, keep percent
+        let attribute = Attribute::from_u8_slice(b"ID=tr\xff%\xff\xfe;note=%zz%4")?;
+        assert_eq!(attribute.get_id(), b"tr\xff%\xff\xfe");
+        assert_eq!(attribute.get(b"note"), Some(&b"%zz%4"[..]));
+
         let slice = b"";
         let attribute = Attribute::from_u8_slice(slice)?;
         assert_eq!(attribute.get_id(), b"");
@@ -348,6 +625,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn gtf_attribute() -> error::Result<()> {
+        let slice = b"gene_id \"ENSG1\"; transcript_id \"ENST1\"; gene_name \"gene1\"; transcript_name \"transcript1\"; exon_id \"ENSE1\"; level 2;";
+
+        let attribute = Attribute::from_gtf_u8_slice(slice, b"gene")?;
+        assert_eq!(attribute.get_id(), b"ENSG1");
+        assert_eq!(attribute.get_name(), b"gene1");
+        assert_eq!(attribute.get_parent(), b"");
+
+        let attribute = Attribute::from_gtf_u8_slice(slice, b"transcript")?;
+        assert_eq!(attribute.get_id(), b"ENST1");
+        assert_eq!(attribute.get_name(), b"transcript1");
+        assert_eq!(attribute.get_parent(), b"ENSG1");
+
+        let attribute = Attribute::from_gtf_u8_slice(
+            b"gene_id \"ENSG1\"; transcript_id \"ENST1\"; tag \"basic\"; tag \"MANE_Select\";",
+            b"transcript",
+        )?;
+        assert_eq!(
+            attribute.get_tags().collect::<Vec<&[u8]>>(),
+            vec![&b"basic"[..], &b"MANE_Select"[..]]
+        );
+        assert_eq!(attribute.get(b"tag"), Some(&b"basic,MANE_Select"[..]));
+
+        let attribute = Attribute::from_gtf_u8_slice(slice, b"exon")?;
+        assert_eq!(attribute.get_id(), b"ENSE1");
+        assert_eq!(attribute.get_name(), b"");
+        assert_eq!(attribute.get_parent(), b"ENST1");
+
+        let attribute = Attribute::from_gtf_u8_slice(slice, b"CDS")?;
+        assert_eq!(attribute.get_id(), b"");
+        assert_eq!(attribute.get_parent(), b"ENST1");
+
+        assert!(is_gtf(slice));
+        assert!(!is_gtf(&test_data::GFF_CSV_RECORD[1][8]));
+        assert!(!is_gtf(b"."));
+
+        let record = csv::ByteRecord::from(vec![
+            "chrA",
+            "HAVANA",
+            "three_prime_utr",
+            "10",
+            "20",
+            ".",
+            "+",
+            ".",
+            "gene_id \"ENSG1\"; transcript_id \"ENST1\";",
+        ]);
+        let annotation = Annotation::from_byte_record(&record)?;
+        assert_eq!(annotation.get_feature(), b"three_prime_UTR");
+        assert_eq!(annotation.get_parent(), b"ENST1");
+
+        Ok(())
+    }
+
     #[test]
     fn annotation() -> error::Result<()> {
         let mut data: Vec<&[u8]> = test_data::GFF_BY_LINE[1].split_str("\t").collect();
@@ -371,13 +703,98 @@ mod tests {
         // Format
         assert_eq!(
             format!("{}", annotation),
-"chrA HAVANA transcript 51 30235 inf + . ID=ENST00000797271.1;Name=transcript_name;Parent=ENSG00000286586.2"
+"chrA HAVANA transcript 51 30235 inf + . ID=ENST00000797271.1;Name=transcript_name;Parent=ENSG00000286586.2;gene_id=ENSG00000286586.2;transcript_id=ENST00000797271.1;gene_type=lncRNA;gene_name=ENSG00000286586;transcript_type=lncRNA;transcript_name=ENST00000797271;level=2;tag=basic,Ensembl_canonical,TAGENE;havana_gene=OTTHUMG00000194565.1"
         );
 
         // Change exon
         let annotation = Annotation::create_child(&annotation, b"exon", 29554, 31097);
 
         assert_eq!(annotation.get_feature(), b"exon");
+        assert!(annotation.get_attribute().get_attributes().is_empty());
+
+        // Attributes of transcript and gene level features are keep
+        for (feature, keep) in [
+            ("mRNA", true),
+            ("ncRNA_gene", true),
+            ("pseudogene", true),
+            ("exon", false),
+        ] {
+            let record = csv::ByteRecord::from(vec![
+                "chrA",
+                "ensembl",
+                feature,
+                "61",
+                "261",
+                ".",
+                "+",
+                ".",
+                "ID=id1;biotype=protein_coding",
+            ]);
+            let annotation = Annotation::from_byte_record(&record)?;
+            assert_eq!(
+                annotation.get_attribute().get_attributes().is_empty(),
+                !keep
+            );
+        }
+
+        // Many parents
+        let record = csv::ByteRecord::from(vec![
+            "chrA",
+            "HAVANA",
+            "exon",
+            "61",
+            "261",
+            ".",
+            "+",
+            ".",
+            "ID=exon1;Parent=tr1,tr2",
+        ]);
+        let annotation = Annotation::from_byte_record(&record)?;
+        assert_eq!(
+            annotation
+                .get_attribute()
+                .get_parents()
+                .collect::<Vec<&[u8]>>(),
+            vec![&b"tr1"[..], &b"tr2"[..]]
+        );
+        let annotations = annotation.clone().split_by_parent();
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].get_parent(), b"tr1");
+        assert_eq!(annotations[1].get_parent(), b"tr2");
+        assert_eq!(annotations[1].get_interval(), annotation.get_interval());
+        assert_eq!(
+            annotations[0].clone().split_by_parent(),
+            vec![annotations[0].clone()]
+        );
+
+        // Encoded comma in parent id
+        let record = csv::ByteRecord::from(vec![
+            "chrA",
+            "HAVANA",
+            "exon",
+            "61",
+            "261",
+            ".",
+            "+",
+            ".",
+            "ID=exon1;Parent=tx%2C1,tx2",
+        ]);
+        let annotation = Annotation::from_byte_record(&record)?;
+        assert_eq!(
+            annotation
+                .get_attribute()
+                .get_parents()
+                .collect::<Vec<&[u8]>>(),
+            vec![&b"tx,1"[..], &b"tx2"[..]]
+        );
+        assert_eq!(
+            format!("{}", annotation.get_attribute()),
+            "ID=exon1;Parent=tx%2C1,tx2"
+        );
+        let annotations = annotation.split_by_parent();
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].get_parent(), b"tx,1");
+        assert_eq!(annotations[1].get_parent(), b"tx2");
 
         // All possible value for Strand
         data[6] = b"-";
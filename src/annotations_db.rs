@@ -18,14 +18,51 @@ pub struct AnnotationsDataBase {
     >,
     transcripts2codings: ahash::AHashMap<Vec<u8>, Vec<annotation::Annotation>>,
     transcripts_id2annotation: ahash::AHashMap<Vec<u8>, annotation::Annotation>,
+    genes_id2annotation: ahash::AHashMap<Vec<u8>, annotation::Annotation>,
 }
 
-impl AnnotationsDataBase {
-    /// Build a AnnotationsDataBase from a reader
+/// Iterate over records of annotations file with their sequence name
+pub fn records(
+    input: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>,
+) -> impl Iterator<Item = error::Result<(Vec<u8>, csv::ByteRecord)>> {
+    csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(false)
+        .comment(Some(b'#'))
+        .from_reader(input)
+        .into_byte_records()
+        .map(|result| {
+            let record = result?;
+            Ok((record.get(0).unwrap_or_default().to_vec(), record))
+        })
+}
+
+/// Annotations before construction of interval trees
+pub struct RawAnnotations {
+    pub(crate) intervals:
+        ahash::AHashMap<Vec<u8>, Vec<clairiere::Node<u64, annotation::Annotation>>>,
+    pub(crate) transcripts2codings: ahash::AHashMap<Vec<u8>, Vec<annotation::Annotation>>,
+    pub(crate) transcripts_id2annotation: ahash::AHashMap<Vec<u8>, annotation::Annotation>,
+    pub(crate) genes_id2annotation: ahash::AHashMap<Vec<u8>, annotation::Annotation>,
+}
+
+impl RawAnnotations {
+    /// Read annotations from a reader
     pub fn from_reader(
         input: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>,
         updown_distance: u64,
     ) -> error::Result<Self> {
+        Self::from_records(
+            records(input).map(|result| result.map(|(_, record)| record)),
+            updown_distance,
+        )
+    }
+
+    /// Read annotations from records of annotations file
+    pub fn from_records<I>(records: I, updown_distance: u64) -> error::Result<Self>
+    where
+        I: IntoIterator<Item = error::Result<csv::ByteRecord>>,
+    {
         let mut intervals_builder: ahash::AHashMap<
             Vec<u8>,
             Vec<clairiere::Node<u64, annotation::Annotation>>,
@@ -35,15 +72,13 @@ impl AnnotationsDataBase {
             ahash::AHashMap::new();
         let mut transcripts_id2annotation: ahash::AHashMap<Vec<u8>, annotation::Annotation> =
             ahash::AHashMap::new();
+        let mut genes_id2annotation: ahash::AHashMap<Vec<u8>, annotation::Annotation> =
+            ahash::AHashMap::new();
 
-        let mut reader = csv::ReaderBuilder::new()
-            .delimiter(b'\t')
-            .has_headers(false)
-            .comment(Some(b'#'))
-            .from_reader(input);
+        for result in records {
+            let record = result?;
 
-        for result in reader.byte_records() {
-            let annotation = match annotation::Annotation::from_byte_record(&result?) {
+            let annotation = match annotation::Annotation::from_byte_record(&record) {
                 Ok(annot) => annot,
                 Err(error) => {
                     log::error!("{}", error);
@@ -51,58 +86,119 @@ impl AnnotationsDataBase {
                 }
             };
 
-            let seqname = annotation.get_seqname();
-            let interval = annotation.get_interval();
+            // Exon or CDS could be shared by many transcripts
+            let annotations = if annotation.is_gene() || annotation.is_transcript() {
+                vec![annotation]
+            } else {
+                annotation.split_by_parent()
+            };
 
-            match annotation.get_feature() {
-                b"exon" | b"start_codon" | b"stop_codon" => {
-                    transcripts2codings
-                        .entry(annotation.get_parent().to_vec())
-                        .or_default()
-                        .push(annotation);
-                }
-                _ => {
-                    if annotation.get_feature() == b"transcript" {
-                        transcripts_id2annotation.insert(
-                            annotation.get_attribute().get_id().to_vec(),
-                            annotation.clone(),
-                        );
+            for annotation in annotations {
+                let seqname = annotation.get_seqname();
+                let interval = annotation.get_interval();
+
+                match annotation.get_feature() {
+                    b"exon" | b"CDS" | b"start_codon" | b"stop_codon" => {
+                        transcripts2codings
+                            .entry(annotation.get_parent().to_vec())
+                            .or_default()
+                            .push(annotation);
                     }
-                    intervals_builder
-                        .entry(seqname.to_vec())
-                        .and_modify(
-                            |tree: &mut Vec<clairiere::Node<u64, annotation::Annotation>>| {
-                                Self::add_annotion(
-                                    tree,
-                                    interval.clone(),
-                                    annotation.clone(),
+                    _ => {
+                        if annotation.is_transcript() {
+                            transcripts_id2annotation.insert(
+                                annotation.get_attribute().get_id().to_vec(),
+                                annotation.clone(),
+                            );
+                        } else if annotation.is_gene() {
+                            genes_id2annotation.insert(
+                                annotation.get_attribute().get_id().to_vec(),
+                                annotation.clone(),
+                            );
+                        }
+                        intervals_builder
+                            .entry(seqname.to_vec())
+                            .and_modify(
+                                |tree: &mut Vec<clairiere::Node<u64, annotation::Annotation>>| {
+                                    AnnotationsDataBase::add_annotion(
+                                        tree,
+                                        interval.clone(),
+                                        annotation.clone(),
+                                        updown_distance,
+                                    );
+                                },
+                            )
+                            .or_insert({
+                                let mut tree = Vec::new();
+
+                                AnnotationsDataBase::add_annotion(
+                                    &mut tree,
+                                    interval,
+                                    annotation,
                                     updown_distance,
                                 );
-                            },
-                        )
-                        .or_insert({
-                            let mut tree = Vec::new();
-
-                            Self::add_annotion(&mut tree, interval, annotation, updown_distance);
-                            tree
-                        });
+                                tree
+                            });
+                    }
                 }
             }
         }
 
+        Ok(Self {
+            intervals: intervals_builder,
+            transcripts2codings,
+            transcripts_id2annotation,
+            genes_id2annotation,
+        })
+    }
+}
+
+impl AnnotationsDataBase {
+    /// Build a AnnotationsDataBase from a reader
+    pub fn from_reader(
+        input: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>,
+        updown_distance: u64,
+    ) -> error::Result<Self> {
+        Ok(Self::from_raw(RawAnnotations::from_reader(
+            input,
+            updown_distance,
+        )?))
+    }
+
+    /// Build a AnnotationsDataBase from records of annotations file
+    pub fn from_records(
+        records: Vec<csv::ByteRecord>,
+        updown_distance: u64,
+    ) -> error::Result<Self> {
+        Ok(Self::from_raw(RawAnnotations::from_records(
+            records.into_iter().map(Ok),
+            updown_distance,
+        )?))
+    }
+
+    /// Build a AnnotationsDataBase from raw annotations
+    pub fn from_raw(raw: RawAnnotations) -> Self {
+        let RawAnnotations {
+            mut intervals,
+            transcripts2codings,
+            transcripts_id2annotation,
+            genes_id2annotation,
+        } = raw;
+
         let mut transcripts_intervals: ahash::AHashMap<
             Vec<u8>,
             clairiere::InterpolateTree<u64, annotation::Annotation, DOMAIN_NUMBER>,
-        > = ahash::AHashMap::with_capacity(intervals_builder.len());
-        for (key, values) in intervals_builder.drain() {
-            transcripts_intervals.insert(key, clairiere::InterpolateTree::new(values.clone()));
+        > = ahash::AHashMap::with_capacity(intervals.len());
+        for (key, values) in intervals.drain() {
+            transcripts_intervals.insert(key, clairiere::InterpolateTree::new(values));
         }
 
-        Ok(Self {
+        Self {
             transcripts_intervals,
             transcripts2codings,
             transcripts_id2annotation,
-        })
+            genes_id2annotation,
+        }
     }
 
     /// Get gene and transcript match with seqname and interval
@@ -131,6 +227,11 @@ impl AnnotationsDataBase {
         self.transcripts_id2annotation.get(transcript_id)
     }
 
+    /// Get gene annotation from id
+    pub fn get_gene(&self, gene_id: &[u8]) -> Option<&annotation::Annotation> {
+        self.genes_id2annotation.get(gene_id)
+    }
+
     /// Add annotation
     fn add_annotion(
         tree: &mut Vec<clairiere::Node<u64, annotation::Annotation>>,
@@ -138,7 +239,7 @@ impl AnnotationsDataBase {
         annotation: annotation::Annotation,
         updown_distance: u64,
     ) {
-        if annotation.get_feature() == b"transcript" {
+        if annotation.is_transcript() {
             let upstream = interval.start.saturating_sub(updown_distance);
 
             tree.push(clairiere::Node::new(
@@ -177,6 +278,7 @@ mod tests {
     /* std use */
 
     /* crate use */
+    use bstr::ByteSlice as _;
 
     /* project use */
     use super::*;
@@ -204,6 +306,13 @@ mod tests {
         value.sort_by_key(|a| (a.get_start(), a.get_stop()));
         assert_eq!(value, truth);
 
+        // gene
+        assert_eq!(
+            annotations.get_gene(test_data::GFF_ANNOTATION[1].get_parent()),
+            Some(&test_data::GFF_ANNOTATION[0])
+        );
+        assert_eq!(annotations.get_gene(b"not_a_gene"), None);
+
         // seqname not present
         assert_eq!(
             annotations.get_annotations(b"chrX", 2300..2301),
@@ -212,4 +321,119 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn multiple_parents() -> error::Result<()> {
+        // Add a second transcript that share all features of ENST00000797271.1
+        let mut gff = Vec::new();
+        for line in test_data::GFF.lines() {
+            if line.contains_str("\ttranscript\t") && line.contains_str("ID=ENST00000797271.1;") {
+                gff.extend(line);
+                gff.push(b'\n');
+                gff.extend(line.replace("ID=ENST00000797271.1;", "ID=shared;"));
+            } else {
+                gff.extend(line.replace(
+                    "Parent=ENST00000797271.1",
+                    "Parent=ENST00000797271.1,shared",
+                ));
+            }
+            gff.push(b'\n');
+        }
+
+        let reader: Box<dyn std::io::Read + Send> = Box::new(std::io::Cursor::new(gff));
+        let annotations = AnnotationsDataBase::from_reader(std::io::BufReader::new(reader), 100)?;
+
+        let truth = test_data::GFF_ANNOTATION[3..8].to_vec();
+        let mut value = annotations
+            .get_coding_annotation(b"ENST00000797271.1")
+            .unwrap()
+            .clone();
+        value.sort_by_key(|a| (a.get_start(), a.get_stop()));
+        assert_eq!(value, truth);
+
+        let mut value = annotations
+            .get_coding_annotation(b"shared")
+            .unwrap()
+            .clone();
+        value.sort_by_key(|a| (a.get_start(), a.get_stop()));
+        assert_eq!(value.len(), truth.len());
+        for (exon, origin) in value.iter().zip(truth.iter()) {
+            assert_eq!(exon.get_interval(), origin.get_interval());
+            assert_eq!(exon.get_parent(), b"shared");
+        }
+        assert!(annotations
+            .get_coding_annotation(b"ENST00000797271.1,shared")
+            .is_none());
+
+        // Five prime UTR is associate to each transcript
+        let mut parents = annotations
+            .get_annotations(b"chrA", 55..56)
+            .iter()
+            .filter(|a| a.get_feature() == b"five_prime_UTR")
+            .map(|a| a.get_parent().to_vec())
+            .collect::<Vec<Vec<u8>>>();
+        parents.sort();
+        assert_eq!(
+            parents,
+            vec![b"ENST00000797271.1".to_vec(), b"shared".to_vec()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn gtf() -> error::Result<()> {
+        let reader: Box<dyn std::io::Read + Send> = Box::new(test_data::GFF);
+        let gff = AnnotationsDataBase::from_reader(std::io::BufReader::new(reader), 100)?;
+
+        let reader: Box<dyn std::io::Read + Send> = Box::new(test_data::GTF);
+        let gtf = AnnotationsDataBase::from_reader(std::io::BufReader::new(reader), 100)?;
+
+        let simplify = |annotations: Vec<&annotation::Annotation>| {
+            let mut values = annotations
+                .iter()
+                .map(|a| {
+                    (
+                        a.get_feature().to_vec(),
+                        a.get_interval(),
+                        a.get_parent().to_vec(),
+                    )
+                })
+                .collect::<Vec<(Vec<u8>, core::ops::Range<u64>, Vec<u8>)>>();
+            values.sort_by_key(|v| (v.1.start, v.1.end, v.0.clone()));
+            values
+        };
+
+        for interval in [13250..13251, 50..52, 121694400..121694401] {
+            assert_eq!(
+                simplify(gtf.get_annotations(b"chrA", interval.clone())),
+                simplify(gff.get_annotations(b"chrA", interval))
+            );
+        }
+
+        for transcript_id in [&b"ENST00000797271.1"[..], b"ENST00000837983.1"] {
+            assert_eq!(
+                gtf.get_transcript(transcript_id)
+                    .map(|a| (a.get_interval(), a.get_parent())),
+                gff.get_transcript(transcript_id)
+                    .map(|a| (a.get_interval(), a.get_parent()))
+            );
+            assert_eq!(
+                simplify(
+                    gtf.get_coding_annotation(transcript_id)
+                        .unwrap()
+                        .iter()
+                        .collect()
+                ),
+                simplify(
+                    gff.get_coding_annotation(transcript_id)
+                        .unwrap()
+                        .iter()
+                        .collect()
+                )
+            );
+        }
+
+        Ok(())
+    }
 }
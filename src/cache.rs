@@ -0,0 +1,666 @@
+//! Binary cache of annotations and sequences database
+
+/* std use */
+use std::io::Read as _;
+
+/* crate use */
+
+/* project use */
+use crate::annotation;
+use crate::annotations_db;
+use crate::error;
+use crate::sequences_db;
+
+/// First bytes of a cache file
+pub const MAGIC: &[u8; 8] = b"VMYTHDB\0";
+
+/// Version of cache format, increase it at each change of format
+pub const VERSION: u32 = 2;
+
+/// Length, modification time and checksum of a source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Source {
+    /// Length of file in bytes
+    pub length: u64,
+    /// Modification time in nanoseconds since unix epoch
+    pub modified: u64,
+    /// Crc32 of file content, None if it isn't compute
+    pub crc32: Option<u32>,
+}
+
+impl Source {
+    /// Get length and modification time of file, checksum is compute only if `checksum` is true,
+    /// file isn't decompressed
+    pub fn from_path(path: &std::path::Path, checksum: bool) -> error::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_nanos() as u64;
+
+        Ok(Self {
+            length: metadata.len(),
+            modified,
+            crc32: if checksum { Some(crc32(path)?) } else { None },
+        })
+    }
+
+    /// Source file match source of cache, checksum is compare only if it's compute
+    fn matches(&self, cache: &Source) -> bool {
+        self.length == cache.length
+            && self.modified == cache.modified
+            && self.crc32.map_or(true, |crc32| Some(crc32) == cache.crc32)
+    }
+}
+
+/// Compute checksum of a file
+fn crc32(path: &std::path::Path) -> error::Result<u32> {
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0; 1 << 16];
+
+    loop {
+        let size = reader.read(&mut buffer)?;
+        if size == 0 {
+            break;
+        }
+        hasher.update(&buffer[..size]);
+    }
+
+    Ok(hasher.finalize())
+}
+
+/// Parameters and source files use to build a cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// [Up|Down]stream transcript distance
+    pub updown_distance: u64,
+    /// Annotations files in order
+    pub annotations: Vec<Source>,
+    /// Reference genome file
+    pub reference: Source,
+}
+
+impl Header {
+    /// Build header from path of source files, checksum of files is compute only if `checksum` is true
+    pub fn from_paths(
+        annotations: &[std::path::PathBuf],
+        reference: &std::path::Path,
+        updown_distance: u64,
+        checksum: bool,
+    ) -> error::Result<Self> {
+        Ok(Self {
+            updown_distance,
+            annotations: annotations
+                .iter()
+                .map(|path| Source::from_path(path, checksum))
+                .collect::<error::Result<Vec<Source>>>()?,
+            reference: Source::from_path(reference, checksum)?,
+        })
+    }
+
+    /// Check header of a cache match this header
+    pub fn check(&self, cache: &Header) -> error::Result<()> {
+        if self.updown_distance != cache.updown_distance {
+            Err(error::Error::CacheStale("updown distance".to_string()).into())
+        } else if self.annotations.len() != cache.annotations.len()
+            || !self
+                .annotations
+                .iter()
+                .zip(cache.annotations.iter())
+                .all(|(source, cache)| source.matches(cache))
+        {
+            Err(error::Error::CacheStale("annotations".to_string()).into())
+        } else if !self.reference.matches(&cache.reference) {
+            Err(error::Error::CacheStale("reference".to_string()).into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Write value in little endian and compute checksum of all written bytes
+struct Encoder<W: std::io::Write> {
+    writer: W,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: std::io::Write> Encoder<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+
+    fn raw(&mut self, value: &[u8]) -> error::Result<()> {
+        self.hasher.update(value);
+        self.writer.write_all(value)?;
+        Ok(())
+    }
+
+    fn u8(&mut self, value: u8) -> error::Result<()> {
+        self.raw(&[value])
+    }
+
+    fn u32(&mut self, value: u32) -> error::Result<()> {
+        self.raw(&value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: u64) -> error::Result<()> {
+        self.raw(&value.to_le_bytes())
+    }
+
+    fn bytes(&mut self, value: &[u8]) -> error::Result<()> {
+        self.u64(value.len() as u64)?;
+        self.raw(value)
+    }
+
+    fn source(&mut self, source: &Source) -> error::Result<()> {
+        self.u64(source.length)?;
+        self.u64(source.modified)?;
+        self.u32(source.crc32.ok_or(error::Error::CacheChecksumMissing)?)
+    }
+
+    fn annotation(&mut self, annotation: &annotation::Annotation) -> error::Result<()> {
+        self.bytes(&annotation.seqname)?;
+        self.bytes(&annotation.source)?;
+        self.bytes(&annotation.feature)?;
+        self.u64(annotation.start)?;
+        self.u64(annotation.stop)?;
+        self.u64(annotation.score.0.to_bits())?;
+        self.u8(match annotation.strand {
+            annotation::Strand::Forward => 0,
+            annotation::Strand::Reverse => 1,
+        })?;
+        self.u8(match annotation.frame {
+            annotation::Frame::Unknow => 0,
+            annotation::Frame::Zero => 1,
+            annotation::Frame::One => 2,
+            annotation::Frame::Two => 3,
+        })?;
+
+        let attribute = &annotation.attribute;
+        self.bytes(&attribute.id)?;
+        self.bytes(&attribute.name)?;
+        self.u64(attribute.parents.len() as u64)?;
+        for parent in &attribute.parents {
+            self.bytes(parent)?;
+        }
+        self.u64(attribute.attributes.len() as u64)?;
+        for (key, value) in &attribute.attributes {
+            self.bytes(key)?;
+            self.bytes(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn annotations(&mut self, annotations: &[annotation::Annotation]) -> error::Result<()> {
+        self.u64(annotations.len() as u64)?;
+        for annotation in annotations {
+            self.annotation(annotation)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write checksum of all previous bytes
+    fn finish(mut self) -> error::Result<()> {
+        let crc32 = self.hasher.finalize();
+        self.writer.write_all(&crc32.to_le_bytes())?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Read value in little endian, any read after end of data is an error
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn raw(&mut self, length: usize) -> error::Result<&'a [u8]> {
+        if length > self.data.len() {
+            return Err(error::Error::CacheCorrupted.into());
+        }
+
+        let (value, rest) = self.data.split_at(length);
+        self.data = rest;
+
+        Ok(value)
+    }
+
+    fn u8(&mut self) -> error::Result<u8> {
+        Ok(self.raw(1)?[0])
+    }
+
+    fn u32(&mut self) -> error::Result<u32> {
+        Ok(u32::from_le_bytes(self.raw(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> error::Result<u64> {
+        Ok(u64::from_le_bytes(self.raw(8)?.try_into()?))
+    }
+
+    fn len(&mut self) -> error::Result<usize> {
+        usize::try_from(self.u64()?).map_err(|_| error::Error::CacheCorrupted.into())
+    }
+
+    fn bytes(&mut self) -> error::Result<Vec<u8>> {
+        let length = self.len()?;
+        Ok(self.raw(length)?.to_vec())
+    }
+
+    fn source(&mut self) -> error::Result<Source> {
+        Ok(Source {
+            length: self.u64()?,
+            modified: self.u64()?,
+            crc32: Some(self.u32()?),
+        })
+    }
+
+    fn annotation(&mut self) -> error::Result<annotation::Annotation> {
+        let seqname = self.bytes()?;
+        let source = self.bytes()?;
+        let feature = self.bytes()?;
+        let start = self.u64()?;
+        let stop = self.u64()?;
+        let score = annotation::Score(f64::from_bits(self.u64()?));
+        let strand = match self.u8()? {
+            0 => annotation::Strand::Forward,
+            1 => annotation::Strand::Reverse,
+            _ => return Err(error::Error::CacheCorrupted.into()),
+        };
+        let frame = match self.u8()? {
+            0 => annotation::Frame::Unknow,
+            1 => annotation::Frame::Zero,
+            2 => annotation::Frame::One,
+            3 => annotation::Frame::Two,
+            _ => return Err(error::Error::CacheCorrupted.into()),
+        };
+
+        let id = self.bytes()?;
+        let name = self.bytes()?;
+        let mut parents = Vec::new();
+        for _ in 0..self.len()? {
+            parents.push(self.bytes()?);
+        }
+        let mut attributes = Vec::new();
+        for _ in 0..self.len()? {
+            attributes.push((self.bytes()?, self.bytes()?));
+        }
+
+        Ok(annotation::Annotation {
+            seqname,
+            source,
+            feature,
+            start,
+            stop,
+            score,
+            strand,
+            frame,
+            attribute: annotation::Attribute {
+                id,
+                name,
+                parents,
+                attributes,
+            },
+        })
+    }
+
+    fn annotations(&mut self) -> error::Result<Vec<annotation::Annotation>> {
+        let length = self.len()?;
+        let mut annotations = Vec::new();
+        for _ in 0..length {
+            annotations.push(self.annotation()?);
+        }
+
+        Ok(annotations)
+    }
+}
+
+/// Write annotations, with nodes of interval trees, and sequences in cache, checksum of sources must be compute
+pub fn write<W: std::io::Write>(
+    writer: W,
+    header: &Header,
+    annotations: &annotations_db::RawAnnotations,
+    sequences: &sequences_db::InMemory,
+) -> error::Result<()> {
+    let mut encoder = Encoder::new(writer);
+
+    encoder.raw(MAGIC)?;
+    encoder.u32(VERSION)?;
+
+    encoder.u64(header.updown_distance)?;
+    encoder.u64(header.annotations.len() as u64)?;
+    for source in &header.annotations {
+        encoder.source(source)?;
+    }
+    encoder.source(&header.reference)?;
+
+    encoder.u64(annotations.intervals.len() as u64)?;
+    for (seqname, nodes) in &annotations.intervals {
+        encoder.bytes(seqname)?;
+        encoder.u64(nodes.len() as u64)?;
+        for node in nodes {
+            encoder.u64(node.start)?;
+            encoder.u64(node.end)?;
+            encoder.annotation(&node.value)?;
+        }
+    }
+
+    encoder.u64(annotations.transcripts2codings.len() as u64)?;
+    for (transcript_id, codings) in &annotations.transcripts2codings {
+        encoder.bytes(transcript_id)?;
+        encoder.annotations(codings)?;
+    }
+
+    for id2annotation in [
+        &annotations.transcripts_id2annotation,
+        &annotations.genes_id2annotation,
+    ] {
+        encoder.u64(id2annotation.len() as u64)?;
+        for (id, annotation) in id2annotation {
+            encoder.bytes(id)?;
+            encoder.annotation(annotation)?;
+        }
+    }
+
+    encoder.u64(sequences.0.len() as u64)?;
+    for (name, sequence) in &sequences.0 {
+        encoder.bytes(name)?;
+        encoder.bytes(sequence)?;
+    }
+
+    encoder.finish()
+}
+
+/// Read a cache, header of cache must match header
+///
+/// Cache is memory mapped, annotations are decoded and sequences are read from mapping on demand.
+/// Checksum of cache is check only if `verify` is true.
+pub fn read(
+    path: &std::path::Path,
+    header: &Header,
+    verify: bool,
+) -> error::Result<(
+    annotations_db::AnnotationsDataBase,
+    sequences_db::SequencesDataBase,
+)> {
+    let file = std::fs::File::open(path)?;
+    // Safety: cache file must not be modified while variant_myth run
+    let data = unsafe { memmap2::Mmap::map(&file)? };
+
+    if !data.starts_with(MAGIC) {
+        return Err(error::Error::CacheBadMagic.into());
+    }
+    let mut decoder = Decoder {
+        data: &data[MAGIC.len()..],
+    };
+    let version = decoder.u32()?;
+    if version != VERSION {
+        return Err(error::Error::CacheVersion {
+            found: version,
+            expected: VERSION,
+        }
+        .into());
+    }
+
+    let (content, crc32) = data
+        .split_last_chunk::<4>()
+        .ok_or(error::Error::CacheCorrupted)?;
+    if verify && crc32fast::hash(content) != u32::from_le_bytes(*crc32) {
+        return Err(error::Error::CacheCorrupted.into());
+    }
+    let mut decoder = Decoder {
+        data: content
+            .get(MAGIC.len() + 4..)
+            .ok_or(error::Error::CacheCorrupted)?,
+    };
+
+    let updown_distance = decoder.u64()?;
+    let mut annotations = Vec::new();
+    for _ in 0..decoder.len()? {
+        annotations.push(decoder.source()?);
+    }
+    let reference = decoder.source()?;
+    header.check(&Header {
+        updown_distance,
+        annotations,
+        reference,
+    })?;
+
+    let mut intervals = ahash::AHashMap::new();
+    for _ in 0..decoder.len()? {
+        let seqname = decoder.bytes()?;
+        let length = decoder.len()?;
+        let mut nodes = Vec::new();
+        for _ in 0..length {
+            let start = decoder.u64()?;
+            let end = decoder.u64()?;
+            nodes.push(clairiere::Node::new(start, end, decoder.annotation()?));
+        }
+        intervals.insert(seqname, nodes);
+    }
+
+    let mut transcripts2codings = ahash::AHashMap::new();
+    for _ in 0..decoder.len()? {
+        transcripts2codings.insert(decoder.bytes()?, decoder.annotations()?);
+    }
+
+    let mut id2annotations = [ahash::AHashMap::new(), ahash::AHashMap::new()];
+    for id2annotation in id2annotations.iter_mut() {
+        for _ in 0..decoder.len()? {
+            id2annotation.insert(decoder.bytes()?, decoder.annotation()?);
+        }
+    }
+    let [transcripts_id2annotation, genes_id2annotation] = id2annotations;
+
+    // sequences stay in mapping, only their position is store
+    let mut sequences = ahash::AHashMap::new();
+    for _ in 0..decoder.len()? {
+        let name = decoder.bytes()?;
+        let length = decoder.len()?;
+        let start = content.len() - decoder.data.len();
+        decoder.raw(length)?;
+        sequences.insert(name, start..start + length);
+    }
+
+    if !decoder.data.is_empty() {
+        return Err(error::Error::CacheCorrupted.into());
+    }
+
+    Ok((
+        annotations_db::AnnotationsDataBase::from_raw(annotations_db::RawAnnotations {
+            intervals,
+            transcripts2codings,
+            transcripts_id2annotation,
+            genes_id2annotation,
+        }),
+        sequences_db::SequencesDataBase::new(sequences_db::Mapped::new(data, sequences)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+    use std::io::Write as _;
+
+    /* crate use */
+
+    /* project use */
+    use super::*;
+    use crate::test_data;
+
+    fn header() -> Header {
+        Header {
+            updown_distance: 100,
+            annotations: vec![Source {
+                length: test_data::GFF.len() as u64,
+                modified: 1,
+                crc32: Some(crc32fast::hash(test_data::GFF)),
+            }],
+            reference: Source {
+                length: test_data::SEQUENCE.len() as u64,
+                modified: 2,
+                crc32: Some(crc32fast::hash(test_data::SEQUENCE)),
+            },
+        }
+    }
+
+    fn read_data(
+        data: &[u8],
+        header: &Header,
+        verify: bool,
+    ) -> error::Result<(
+        annotations_db::AnnotationsDataBase,
+        sequences_db::SequencesDataBase,
+    )> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(data)?;
+        file.flush()?;
+
+        read(file.path(), header, verify)
+    }
+
+    fn cache() -> error::Result<Vec<u8>> {
+        let reader: Box<dyn std::io::Read + Send> = Box::new(test_data::GFF);
+        let annotations =
+            annotations_db::RawAnnotations::from_reader(std::io::BufReader::new(reader), 100)?;
+        let reader: Box<dyn std::io::Read + Send> = Box::new(test_data::SEQUENCE);
+        let sequences = sequences_db::InMemory::from_reader(std::io::BufReader::new(reader))?;
+
+        let mut output = Vec::new();
+        write(&mut output, &header(), &annotations, &sequences)?;
+
+        Ok(output)
+    }
+
+    #[test]
+    fn source() -> error::Result<()> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(test_data::GFF)?;
+
+        let modified = std::fs::metadata(file.path())?
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_nanos() as u64;
+
+        assert_eq!(
+            Source::from_path(file.path(), false)?,
+            Source {
+                length: test_data::GFF.len() as u64,
+                modified,
+                crc32: None,
+            }
+        );
+        assert_eq!(
+            Source::from_path(file.path(), true)?,
+            Source {
+                length: test_data::GFF.len() as u64,
+                modified,
+                crc32: Some(crc32fast::hash(test_data::GFF)),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip() -> error::Result<()> {
+        let data = cache()?;
+        assert!(data.starts_with(MAGIC));
+
+        let (annotations, sequences) = read_data(&data, &header(), true)?;
+
+        let reader: Box<dyn std::io::Read + Send> = Box::new(test_data::GFF);
+        let truth =
+            annotations_db::AnnotationsDataBase::from_reader(std::io::BufReader::new(reader), 100)?;
+
+        for interval in [
+            0..1,
+            13250..13251,
+            50..52,
+            30200..30300,
+            121694400..121694401,
+        ] {
+            let mut result = annotations.get_annotations(b"chrA", interval.clone());
+            result.sort_by_key(|a| (a.get_start(), a.get_stop(), a.get_feature().to_vec()));
+            let mut expect = truth.get_annotations(b"chrA", interval);
+            expect.sort_by_key(|a| (a.get_start(), a.get_stop(), a.get_feature().to_vec()));
+            assert_eq!(result, expect);
+        }
+
+        for transcript_id in [&b"ENST00000797271.1"[..], b"ENST00000837983.1"] {
+            assert_eq!(
+                annotations.get_transcript(transcript_id),
+                truth.get_transcript(transcript_id)
+            );
+            assert_eq!(
+                annotations.get_coding_annotation(transcript_id),
+                truth.get_coding_annotation(transcript_id)
+            );
+        }
+        assert_eq!(
+            annotations.get_gene(b"ENSG00000286586.2"),
+            Some(&test_data::GFF_ANNOTATION[0])
+        );
+
+        assert_eq!(
+            sequences.get_interval(b"chrA", &(100..120))?,
+            test_data::SEQUENCE_DB.get_interval(b"chrA", &(100..120))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject() -> error::Result<()> {
+        let data = cache()?;
+
+        // Source without checksum only check length and modification time
+        let mut unverified = header();
+        unverified.annotations[0].crc32 = None;
+        unverified.reference.crc32 = None;
+        assert!(read_data(&data, &unverified, false).is_ok());
+
+        // Stale
+        let mut stale = header();
+        stale.updown_distance = 5000;
+        assert!(read_data(&data, &stale, false).is_err());
+
+        let mut stale = header();
+        stale.annotations[0].crc32 = stale.annotations[0].crc32.map(|crc32| crc32 + 1);
+        assert!(read_data(&data, &stale, false).is_err());
+
+        let mut stale = unverified.clone();
+        stale.annotations[0].modified += 1;
+        assert!(read_data(&data, &stale, false).is_err());
+
+        let mut stale = header();
+        stale.annotations.push(stale.reference);
+        assert!(read_data(&data, &stale, false).is_err());
+
+        let mut stale = unverified.clone();
+        stale.reference.length += 1;
+        assert!(read_data(&data, &stale, false).is_err());
+
+        // Not a cache
+        assert!(read_data(test_data::GFF, &header(), false).is_err());
+
+        // Other version
+        let mut other = data.clone();
+        other[MAGIC.len()] += 1;
+        assert!(read_data(&other, &header(), false).is_err());
+
+        // Corrupted or truncated
+        let mut corrupted = data.clone();
+        corrupted[data.len() / 2] ^= 1;
+        assert!(read_data(&corrupted, &header(), true).is_err());
+        assert!(read_data(&data[..data.len() - 10], &header(), false).is_err());
+
+        Ok(())
+    }
+}
@@ -8,20 +8,43 @@ use std::io::Read;
 use enumflags2::BitFlag as _;
 
 /* project use */
+use crate::cache;
 use crate::error;
 use crate::output;
+use crate::variant;
 use crate::variant2myth;
 
+/// Path `-` is read in stdin or write in stdout
+fn is_stdio(path: &std::path::Path) -> bool {
+    path == std::path::Path::new("-")
+}
+
 fn get_reader(
     path: &std::path::PathBuf,
 ) -> error::Result<Box<dyn std::io::Read + std::marker::Send>> {
-    let file = std::fs::File::open(path)?;
-    let boxed = Box::new(file);
+    let boxed: Box<dyn std::io::Read + std::marker::Send> = if is_stdio(path) {
+        Box::new(std::io::stdin())
+    } else {
+        Box::new(std::fs::File::open(path)?)
+    };
     let (reader, _compression) = niffler::send::get_reader(boxed)?;
 
     Ok(reader)
 }
 
+#[cfg(any(feature = "json", feature = "vcf"))]
+fn get_writer(
+    path: &std::path::Path,
+) -> error::Result<std::io::BufWriter<Box<dyn std::io::Write + std::marker::Send>>> {
+    let boxed: Box<dyn std::io::Write + std::marker::Send> = if is_stdio(path) {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(std::fs::File::create(path)?)
+    };
+
+    Ok(std::io::BufWriter::new(boxed))
+}
+
 /// A variant annotater.
 #[derive(clap::Parser, std::fmt::Debug)]
 #[clap(
@@ -33,18 +56,46 @@ fn get_reader(
 #[command(propagate_version = true)]
 pub struct Command {
     // Specific option
-    /// Variants path
-    #[clap(short = 'i', long = "input", required = true)]
+    /// Variants path, vcf or bcf, `-` read stdin, required except by index subcommand
+    #[clap(short = 'i', long = "input")]
     variant_paths: Vec<std::path::PathBuf>,
 
+    /// Only annotate region chr:start-end, or chr, chr:pos, chr:start-, input need an index
+    #[clap(long = "region")]
+    regions: Vec<variant::Region>,
+
+    /// Only annotate regions of bed file, input need an index
+    #[clap(long = "regions-file")]
+    regions_path: Option<std::path::PathBuf>,
+
     /// Reference genome path
     #[clap(short = 'r', long = "reference")]
     reference_path: std::path::PathBuf,
 
-    /// Annotation path
+    /// Read reference on demand with fai index, and gzi index if bgzip compressed
+    #[clap(long = "indexed-reference")]
+    indexed_reference: bool,
+
+    /// Number of reference blocks, of 65,536 bases, keep in memory with --indexed-reference
+    #[clap(long = "reference-cache", default_value = "1024")]
+    reference_cache: usize,
+
+    /// Annotation path, gff3 or gtf
     #[clap(short = 'a', long = "annotations", required = true)]
     annotations_path: Vec<std::path::PathBuf>,
 
+    /// Cache build by index subcommand, read in place of annotations and reference
+    #[clap(long = "cache", conflicts_with = "indexed_reference")]
+    cache_path: Option<std::path::PathBuf>,
+
+    /// Check checksum of cache, annotations and reference with --cache, all files are read
+    #[clap(long = "verify-cache", requires = "cache_path")]
+    verify_cache: bool,
+
+    /// Load annotations and reference one chromosome at a time, variants must be group by chromosome
+    #[clap(long = "stream", conflicts_with = "cache_path")]
+    stream: bool,
+
     /// Translate table path, if not set use human
     #[clap(short = 't', long = "translate")]
     translate_path: Option<std::path::PathBuf>,
@@ -57,6 +108,49 @@ pub struct Command {
     #[clap(short = 'c', long = "annotators-choices")]
     annotators_choices: Vec<variant2myth::AnnotatorsChoicesRaw>,
 
+    /// Number of intronic bases of splice donor and acceptor site
+    #[clap(long = "splice-site", default_value = "2")]
+    splice_site: u64,
+
+    /// Number of exonic bases of splice region
+    #[clap(
+        long = "splice-region-exon",
+        default_value = "3",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    splice_region_exon: u64,
+
+    /// Number of intronic bases of splice region
+    #[clap(long = "splice-region-intron", default_value = "8")]
+    splice_region_intron: u64,
+
+    /// Disable trim and left alignment of variant against reference
+    #[clap(long = "no-normalize")]
+    no_normalize: bool,
+
+    /// What to do with variant where REF didn't match reference genome
+    #[clap(long = "ref-mismatch", default_value = "warn")]
+    ref_mismatch: variant2myth::RefMismatch,
+
+    /// What to do with vcf record that can't be parse
+    #[clap(long = "bad-record", default_value = "fail")]
+    bad_record: variant2myth::BadRecord,
+
+    /// Which transcripts are report
+    #[clap(long = "transcript-selection", default_value = "all")]
+    transcript_selection: TranscriptSelection,
+
+    /// File with one transcript id by line, required by allowlist transcript selection
+    #[clap(
+        long = "transcript-allowlist",
+        required_if_eq("transcript_selection", "allowlist")
+    )]
+    transcript_allowlist: Option<std::path::PathBuf>,
+
+    /// Attribute keys of transcript, or gene, copy in annotations (comma separated)
+    #[clap(long = "attributes", value_delimiter = ',')]
+    attributes: Vec<String>,
+
     /// Output subcommand
     #[clap(subcommand)]
     pub output: OutputSubCommand,
@@ -81,14 +175,57 @@ pub struct Command {
 }
 
 impl Command {
-    /// Get variant reader
-    pub fn variant(
+    /// Get variant paths, each path is associate to output path with same index
+    pub fn variant_paths(&self) -> error::Result<&[std::path::PathBuf]> {
+        if self.variant_paths.is_empty() {
+            return Err(error::Error::NoVariantInput.into());
+        }
+
+        if self.variant_paths.len() != self.output.paths().len() {
+            return Err(error::Error::InputOutputMismatch {
+                inputs: self.variant_paths.len(),
+                outputs: self.output.paths().len(),
+            }
+            .into());
+        }
+
+        if self.variant_paths.iter().filter(|p| is_stdio(p)).count() > 1
+            || self.output.paths().iter().filter(|p| is_stdio(p)).count() > 1
+        {
+            return Err(error::Error::StdioMultipleUse.into());
+        }
+
+        Ok(&self.variant_paths)
+    }
+
+    /// Get reader of one variant path
+    pub fn variant_reader(
         &self,
-    ) -> error::Result<Vec<std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>>> {
-        self.variant_paths
-            .iter()
-            .map(|p| get_reader(p).map(std::io::BufReader::new))
-            .collect()
+        path: &std::path::PathBuf,
+    ) -> error::Result<std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>> {
+        let reader: Box<dyn std::io::Read + std::marker::Send> = match self.regions()? {
+            Some(_) if is_stdio(path) => return Err(error::Error::RegionStdin.into()),
+            Some(regions) => Box::new(variant::RegionReader::from_path(path, &regions)?),
+            None => variant::decode(get_reader(path)?)?,
+        };
+
+        Ok(std::io::BufReader::new(reader))
+    }
+
+    /// Get regions set by --region and --regions-file, None if no region is set
+    pub fn regions(&self) -> error::Result<Option<Vec<variant::Region>>> {
+        if self.regions.is_empty() && self.regions_path.is_none() {
+            return Ok(None);
+        }
+
+        let mut regions = self.regions.clone();
+        if let Some(path) = &self.regions_path {
+            regions.extend(variant::Region::from_bed(
+                get_reader(path).map(std::io::BufReader::new)?,
+            )?);
+        }
+
+        Ok(Some(regions))
     }
 
     /// Get reference reader
@@ -98,6 +235,12 @@ impl Command {
         get_reader(&self.reference_path).map(std::io::BufReader::new)
     }
 
+    /// Get reference path and number of cached blocks, if reference is read with its index
+    pub fn indexed_reference(&self) -> Option<(&std::path::Path, usize)> {
+        self.indexed_reference
+            .then_some((self.reference_path.as_path(), self.reference_cache))
+    }
+
     /// Get annotations reader
     pub fn annotations(
         &self,
@@ -112,6 +255,32 @@ impl Command {
         Ok(std::io::BufReader::new(handle))
     }
 
+    /// Get cache path
+    pub fn cache_path(&self) -> Option<&std::path::Path> {
+        self.cache_path.as_deref()
+    }
+
+    /// Get if annotations and reference are load by chromosome
+    pub fn stream(&self) -> bool {
+        self.stream
+    }
+
+    /// Get if checksum of cache and sources are check
+    pub fn verify_cache(&self) -> bool {
+        self.verify_cache
+    }
+
+    /// Get header of cache build from annotations and reference, checksum of sources is compute
+    /// only if `checksum` is true
+    pub fn cache_header(&self, checksum: bool) -> error::Result<cache::Header> {
+        cache::Header::from_paths(
+            &self.annotations_path,
+            &self.reference_path,
+            self.updown_distance(),
+            checksum,
+        )
+    }
+
     /// Get translate reader
     pub fn translate(
         &self,
@@ -137,6 +306,72 @@ impl Command {
             })
     }
 
+    /// Get size of splice windows, splice region must contains splice site
+    pub fn splice_window(&self) -> error::Result<variant2myth::SpliceWindow> {
+        if self.splice_region_intron < self.splice_site {
+            return Err(error::Error::SpliceWindowBad {
+                site: self.splice_site,
+                region_intron: self.splice_region_intron,
+            }
+            .into());
+        }
+
+        Ok(variant2myth::SpliceWindow {
+            site: self.splice_site,
+            region_exon: self.splice_region_exon,
+            region_intron: self.splice_region_intron,
+        })
+    }
+
+    /// Get if variant are normalize
+    pub fn normalize(&self) -> bool {
+        !self.no_normalize
+    }
+
+    /// Get what to do with variant where REF didn't match reference genome
+    pub fn ref_mismatch(&self) -> variant2myth::RefMismatch {
+        self.ref_mismatch
+    }
+
+    /// Get what to do with vcf record that can't be parse
+    pub fn bad_record(&self) -> variant2myth::BadRecord {
+        self.bad_record
+    }
+
+    /// Get which transcripts are report
+    pub fn transcript_selection(&self) -> error::Result<variant2myth::TranscriptSelection> {
+        match self.transcript_selection {
+            TranscriptSelection::All => Ok(variant2myth::TranscriptSelection::All),
+            TranscriptSelection::MostSevere => Ok(variant2myth::TranscriptSelection::MostSevere),
+            TranscriptSelection::Canonical => Ok(variant2myth::TranscriptSelection::Canonical),
+            TranscriptSelection::Allowlist => {
+                // clap check allowlist is set
+                let reader = std::io::BufReader::new(get_reader(
+                    self.transcript_allowlist.as_ref().unwrap(),
+                )?);
+
+                let mut allowlist = ahash::AHashSet::new();
+                for line in std::io::BufRead::lines(reader) {
+                    let line = line?;
+                    let id = line.trim();
+                    if !id.is_empty() && !id.starts_with('#') {
+                        allowlist.insert(id.as_bytes().to_vec());
+                    }
+                }
+
+                Ok(variant2myth::TranscriptSelection::Allowlist(allowlist))
+            }
+        }
+    }
+
+    /// Get attribute keys copy in annotations
+    pub fn attributes(&self) -> Vec<Vec<u8>> {
+        self.attributes
+            .iter()
+            .map(|key| key.as_bytes().to_vec())
+            .collect()
+    }
+
     /// Get number of thread
     #[cfg(feature = "parallel")]
     pub fn threads(&self) -> usize {
@@ -159,6 +394,19 @@ impl Command {
     }
 }
 
+/// Choose which transcripts are report
+#[derive(clap::ValueEnum, std::clone::Clone, std::marker::Copy, std::fmt::Debug)]
+pub enum TranscriptSelection {
+    /// All transcripts overlap by variant
+    All,
+    /// One transcript by gene, with the most severe impact
+    MostSevere,
+    /// Transcripts tagged Ensembl_canonical or MANE_Select
+    Canonical,
+    /// Transcripts listed in --transcript-allowlist file
+    Allowlist,
+}
+
 /// Subcommand to control how output are write
 #[derive(clap::Subcommand, std::fmt::Debug)]
 pub enum OutputSubCommand {
@@ -168,16 +416,50 @@ pub enum OutputSubCommand {
     /// Output are write in json format
     #[cfg(feature = "json")]
     Json(Json),
+    /// Output are write in vcf format
+    #[cfg(feature = "vcf")]
+    Vcf(Vcf),
+    /// Write annotations and reference in a cache, variants aren't annotate
+    Index(Index),
 }
 
 impl OutputSubCommand {
-    /// Create myth writer
-    pub fn writers(&self) -> error::Result<Vec<Box<dyn output::MythWriter + std::marker::Send>>> {
+    /// Create myth writer of one output path, attribute keys are only used by parquet writer
+    pub fn writer(
+        &self,
+        path: &std::path::Path,
+        #[allow(unused_variables)] attributes: &[Vec<u8>],
+    ) -> error::Result<Box<dyn output::MythWriter + std::marker::Send>> {
         match self {
             #[cfg(feature = "parquet")]
-            OutputSubCommand::Parquet(obj) => obj.writers(),
+            OutputSubCommand::Parquet(obj) => {
+                if is_stdio(path) {
+                    return Err(error::Error::ParquetStdout.into());
+                }
+
+                obj.writer(
+                    std::fs::File::create(path).map(std::io::BufWriter::new)?,
+                    attributes,
+                )
+            }
             #[cfg(feature = "json")]
-            OutputSubCommand::Json(obj) => obj.writers(),
+            OutputSubCommand::Json(obj) => obj.writer(get_writer(path)?),
+            #[cfg(feature = "vcf")]
+            OutputSubCommand::Vcf(obj) => obj.writer(get_writer(path)?),
+            OutputSubCommand::Index(_) => unreachable!("index subcommand didn't write myth"),
+        }
+    }
+
+    /// Get output paths, empty for index subcommand
+    pub fn paths(&self) -> &[std::path::PathBuf] {
+        match self {
+            #[cfg(feature = "parquet")]
+            OutputSubCommand::Parquet(obj) => &obj.paths,
+            #[cfg(feature = "json")]
+            OutputSubCommand::Json(obj) => &obj.paths,
+            #[cfg(feature = "vcf")]
+            OutputSubCommand::Vcf(obj) => &obj.paths,
+            OutputSubCommand::Index(_) => &[],
         }
     }
 }
@@ -197,19 +479,17 @@ pub struct Parquet {
 
 #[cfg(feature = "parquet")]
 impl Parquet {
-    /// Create myth writer
-    pub fn writers(&self) -> error::Result<Vec<Box<dyn output::MythWriter + std::marker::Send>>> {
-        let mut result = Vec::new();
-
-        for p in &self.paths {
-            result.push(Box::new(output::ParquetWriter::new(
-                std::fs::File::create(p).map(std::io::BufWriter::new)?,
-                self.block_size(),
-            )?)
-                as Box<dyn output::MythWriter + std::marker::Send>);
-        }
-
-        Ok(result)
+    /// Create myth writer, each attribute keys is write in a column
+    pub fn writer(
+        &self,
+        file: std::io::BufWriter<std::fs::File>,
+        attributes: &[Vec<u8>],
+    ) -> error::Result<Box<dyn output::MythWriter + std::marker::Send>> {
+        Ok(Box::new(output::ParquetWriter::new(
+            file,
+            self.block_size(),
+            attributes.to_vec(),
+        )?))
     }
 
     /// Get block_size
@@ -222,7 +502,7 @@ impl Parquet {
 #[derive(clap::Args, std::fmt::Debug)]
 #[cfg(feature = "json")]
 pub struct Json {
-    /// Output path
+    /// Output path, `-` write stdout
     #[clap(short = 'p', long = "path", required = true)]
     paths: Vec<std::path::PathBuf>,
 
@@ -234,18 +514,11 @@ pub struct Json {
 #[cfg(feature = "json")]
 impl Json {
     /// Create myth writer
-    pub fn writers(&self) -> error::Result<Vec<Box<dyn output::MythWriter + std::marker::Send>>> {
-        let mut result = Vec::new();
-
-        for p in &self.paths {
-            result.push(Box::new(output::JsonWriter::new(
-                std::fs::File::create(p).map(std::io::BufWriter::new)?,
-                self.format(),
-            )?)
-                as Box<dyn output::MythWriter + std::marker::Send>);
-        }
-
-        Ok(result)
+    pub fn writer(
+        &self,
+        output: std::io::BufWriter<Box<dyn std::io::Write + std::marker::Send>>,
+    ) -> error::Result<Box<dyn output::MythWriter + std::marker::Send>> {
+        Ok(Box::new(output::JsonWriter::new(output, self.format())?))
     }
 
     /// Get format value
@@ -253,3 +526,38 @@ impl Json {
         self.json_format.unwrap_or_default()
     }
 }
+
+/// Output are write in vcf format
+#[derive(clap::Args, std::fmt::Debug)]
+#[cfg(feature = "vcf")]
+pub struct Vcf {
+    /// Output path, `-` write stdout
+    #[clap(short = 'p', long = "path", required = true)]
+    paths: Vec<std::path::PathBuf>,
+}
+
+#[cfg(feature = "vcf")]
+impl Vcf {
+    /// Create myth writer
+    pub fn writer(
+        &self,
+        output: std::io::BufWriter<Box<dyn std::io::Write + std::marker::Send>>,
+    ) -> error::Result<Box<dyn output::MythWriter + std::marker::Send>> {
+        Ok(Box::new(output::VcfWriter::new(output)?))
+    }
+}
+
+/// Write annotations and reference in a cache
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Index {
+    /// Cache path
+    #[clap(short = 'p', long = "path", required = true)]
+    path: std::path::PathBuf,
+}
+
+impl Index {
+    /// Create cache writer
+    pub fn writer(&self) -> error::Result<std::io::BufWriter<std::fs::File>> {
+        Ok(std::io::BufWriter::new(std::fs::File::create(&self.path)?))
+    }
+}
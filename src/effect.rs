@@ -89,6 +89,7 @@ impl From<&Effect> for Impact {
 		| Effect::StopRetainedVariant // FrameShiftAfterCDS -> Modifer | NonSynonymousStop -> Low | SynonymousStop -> Low
 		| Effect::Duplication // Large, Exon -> High | Gene, Transcript -> Moderate
 		| Effect::Ignore // variant is ignore so…
+		| Effect::InvalidRecord // variant can't be read so…
 		=> Impact::Other,
 
         }
@@ -100,7 +101,7 @@ impl From<&Effect> for Impact {
 #[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub enum Effect {
     /// A sequence variant whereby two genes, on alternate strands have become joined.
-    BidirectionalGeneFusion,
+    BidirectionalGeneFusion, // Used
     /// Structural unit composed of a nucleic acid molecule which controls its own replication through the interaction of specific proteins at one or more origins of replication.
     Chromosome,
     /// A kind of chromosome variation where the chromosome complement is not an exact multiple of the haploid number.
@@ -122,43 +123,45 @@ pub enum Effect {
     /// A sequence variant located 3' of a gene.
     DownstreamGeneVariant,
     /// An insertion which derives from, or is identical in sequence to, nucleotides present at a known location in the genome.
-    Duplication,
+    Duplication, // Used
     /// A sequence variant whereby an exon is lost from the transcript.
-    ExonLossVariant,
+    ExonLossVariant, // Used
     /// A region of an exon.
-    ExonRegion,
+    ExonRegion, // Used
     /// A sequence variant, caused by an alteration of the genomic sequence, where the deletion, is greater than the extent of the underlying genomic features.
-    FeatureAblation,
+    FeatureAblation, // Used
     /// A sequence variant that causes the extension of a genomic feature, with regard to the reference sequence.
-    FeatureElongation,
+    FeatureElongation, // Used
     /// A sequence variant, caused by an alteration of the genomic sequence, where a deletion fuses genomic features.
     FeatureFusion,
     /// A sequence variant which causes a disruption of the translational reading frame, because the number of nucleotides inserted or deleted is not a multiple of three.
     FrameshiftVariant, // Used
     /// A sequence variant whereby a two genes have become joined.
-    GeneFusion,
+    GeneFusion, // Used
     /// A sequence variant where the structure of the gene is changed.
-    GeneVariant,
+    GeneVariant, // Used
     /// A codon variant that changes at least one base of the first codon of a transcript.
     Ignore,
     /// Variant are ignore
     InitiatorCodonVariant,
+    /// Vcf record can't be parse
+    InvalidRecord,
     /// A region containing or overlapping no genes that is bounded on either side by a gene, or bounded by a gene and the end of the chromosome.
     IntergenicRegion,
     /// A sequence variant located in the intergenic region, between genes.
-    IntragenicVariant,
+    IntragenicVariant, // Used
     /// A transcript variant occurring within an intron.
-    IntronVariant,
+    IntronVariant, // Used
     /// A continuous nucleotide sequence is inverted in the same position.
-    Inversion,
+    Inversion, // Used
     /// Small, ~22-nt, RNA molecule that is the endogenous transcript of a miRNA gene (or the product of other non coding RNA genes). Micro RNAs are produced from precursor molecules (SO:0001244) that can form local hairpin structures, which ordinarily are processed (usually via the Dicer pathway) such that a single miRNA molecule accumulates from one arm of a hairpin precursor molecule. Micro RNAs may trigger the cleavage of their target molecules or act as translational repressors.
     MiRna,
     /// A sequence variant, that changes one or more bases, resulting in a different amino acid sequence but where the length is preserved.
     MissenseVariant,
     /// A sequence variant that changes non-coding exon sequence in a non-coding transcript.
-    NonCodingTranscriptExonVariant,
+    NonCodingTranscriptExonVariant, // Used
     /// A transcript variant of a non coding RNA gene.
-    NonCodingTranscriptVariant,
+    NonCodingTranscriptVariant, // Used
     /// A sequence variant that causes the reduction of a the 3' UTR with regard to the reference sequence.
     ThreePrimeUtrTruncation,
     /// A UTR variant of the 3' UTR.
@@ -184,7 +187,7 @@ pub enum Effect {
     /// A splice variant that changes the 2 base pair region at the 5' end of an intron.
     SpliceDonorVariant, // Used
     /// A sequence variant in which a change has occurred within the region of the splice site, either within 1-3 bases of the exon or 3-8 bases of the intron.
-    SpliceRegionVariant,
+    SpliceRegionVariant, // Used
     /// A codon variant that changes at least one base of the canonical start codon.
     StartLost,
     /// A sequence variant where at least one base in the start codon is changed, but the start remains.
@@ -204,7 +207,7 @@ pub enum Effect {
     /// A feature ablation whereby the deleted region includes a transcription factor binding site.
     TfbsAblation,
     /// A feature ablation whereby the deleted region includes a transcript feature.
-    TranscriptAblation,
+    TranscriptAblation, // Used
     /// A sequence variant located 5' of a gene.
     UpstreamGeneVariant,
 }
@@ -275,6 +278,7 @@ impl From<Effect> for Impact {
 		| Effect::StopRetainedVariant // FrameShiftAfterCDS -> Modifer | NonSynonymousStop -> Low | SynonymousStop -> Low
 		| Effect::Duplication // Large, Exon -> High | Gene, Transcript -> Moderate
 		| Effect::Ignore // variant is ignore so…
+		| Effect::InvalidRecord // variant can't be read so…
 		=> Impact::Other,
 
         }
@@ -306,6 +310,7 @@ impl From<Effect> for Vec<u8> {
             Effect::GeneVariant => b"gene_variant".to_vec(),
             Effect::Ignore => b"ignore".to_vec(),
             Effect::InitiatorCodonVariant => b"initiator_codon_variant".to_vec(),
+            Effect::InvalidRecord => b"invalid_record".to_vec(),
             Effect::IntergenicRegion => b"intergenic_region".to_vec(),
             Effect::IntragenicVariant => b"intragenic_variant".to_vec(),
             Effect::IntronVariant => b"intron_variant".to_vec(),
@@ -488,6 +493,7 @@ mod tests {
         assert_eq!(Impact::from(Effect::GeneFusion), Impact::High);
         assert_eq!(Impact::from(Effect::GeneVariant), Impact::Modifier);
         assert_eq!(Impact::from(Effect::Ignore), Impact::Other);
+        assert_eq!(Impact::from(Effect::InvalidRecord), Impact::Other);
         assert_eq!(Impact::from(Effect::InitiatorCodonVariant), Impact::Low);
         assert_eq!(Impact::from(Effect::IntergenicRegion), Impact::Modifier);
         assert_eq!(Impact::from(Effect::IntragenicVariant), Impact::Modifier);
@@ -618,6 +624,10 @@ mod tests {
             b"gene_variant".to_vec()
         );
         assert_eq!(Vec::<u8>::from(Effect::Ignore), b"ignore".to_vec());
+        assert_eq!(
+            Vec::<u8>::from(Effect::InvalidRecord),
+            b"invalid_record".to_vec()
+        );
         assert_eq!(
             Vec::<u8>::from(Effect::InitiatorCodonVariant),
             b"initiator_codon_variant".to_vec()
@@ -39,9 +39,24 @@ pub enum Error {
     VcfBadRecord,
 
     /// Vcf record
-    #[error("Structural variant without SVLEN")]
+    #[error("Structural variant without SVLEN or END")]
     VcfStructVariantNoSvLen,
 
+    /// Breakend notation of ALT column is invalid
+    #[error("Breakend notation of ALT column is invalid")]
+    VcfBadBreakend,
+
+    /// Vcf record can't be parse, with line number and raw text
+    #[error("Vcf line {line} can't be parse, {message}: {record}")]
+    VcfInvalidRecord {
+        /// Line number in vcf
+        line: u64,
+        /// Raw text of record
+        record: String,
+        /// Parsing error
+        message: String,
+    },
+
     /// Error in attribute name
     #[error("Attribute name not support {0}")]
     AttributeNameNotSupport(String),
@@ -58,6 +73,97 @@ pub enum Error {
         /// Name of sequence
         name: String,
     },
+
+    /// No variant file
+    #[error("No variant file, --input is required")]
+    NoVariantInput,
+
+    /// Splice region didn't contains splice site
+    #[error(
+        "--splice-region-intron {region_intron} must be greater or equal to --splice-site {site}"
+    )]
+    SpliceWindowBad {
+        /// Number of intronic bases of splice site
+        site: u64,
+        /// Number of intronic bases of splice region
+        region_intron: u64,
+    },
+
+    /// Number of variant files and output files are different
+    #[error(
+        "{inputs} variant files but {outputs} output files, each variant file needs one output"
+    )]
+    InputOutputMismatch {
+        /// Number of variant files
+        inputs: usize,
+        /// Number of output files
+        outputs: usize,
+    },
+
+    /// Path `-` is used by more than one input or more than one output
+    #[error("Stdin or stdout, path '-', can be used by only one input and one output")]
+    StdioMultipleUse,
+
+    /// Parquet writer need to seek in output
+    #[error("Parquet output can't be write in stdout, use a file or json or vcf output")]
+    ParquetStdout,
+
+    /// File didn't start with cache magic number
+    #[error("File isn't a variant_myth cache")]
+    CacheBadMagic,
+
+    /// Cache format version isn't supported
+    #[error("Cache version {found} isn't supported, expected version {expected}")]
+    CacheVersion {
+        /// Version of cache file
+        found: u32,
+        /// Version support by this variant_myth
+        expected: u32,
+    },
+
+    /// Cache file is truncated or checksum didn't match
+    #[error("Cache is truncated or corrupted")]
+    CacheCorrupted,
+
+    /// Checksum of a source file isn't compute before cache write
+    #[error("Checksum of annotations and reference is required to write cache")]
+    CacheChecksumMissing,
+
+    /// Cache was build with other source files or parameters
+    #[error("Cache is stale, {0} changed since cache creation, rebuild it with index subcommand")]
+    CacheStale(String),
+
+    /// Line of fasta index can't be parse or didn't match fasta
+    #[error("Fasta index line {0} can't be parse or didn't match fasta")]
+    FaiBadRecord(usize),
+
+    /// Variants of a sequence aren't contiguous in streaming mode
+    #[error("Variants on {0} aren't contiguous, sort vcf by chromosome or disable --stream")]
+    UnsortedVariant(String),
+
+    /// Records of a sequence aren't contiguous in annotations or reference in streaming mode
+    #[error("Records of {0} aren't contiguous in annotations or reference, group them by sequence or disable --stream")]
+    UnsortedSeqname(String),
+
+    /// Reference is bgzip compress but gzi index is missing
+    #[error("Reference is compressed, bgzip index {0} is required")]
+    GziMissing(String),
+
+    /// Region query need a tabix or csi index
+    #[error("Region query need index {0}.tbi or {0}.csi")]
+    VariantIndexMissing(String),
+
+    /// Tabix or csi index can't be parse
+    #[error("Variant index {0} can't be parse")]
+    VariantIndexBad(String),
+
+    /// Region can't be parse
+    #[error("Region {0} can't be parse, expect chr, chr:pos, chr:start-end or chr:start-")]
+    RegionBad(String),
+
+    /// Region query need to seek in variant file
+    #[error("Region query can't read stdin, variant file must be bgzip compressed and indexed")]
+    RegionStdin,
 }
 
 /// Alias of result
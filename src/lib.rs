@@ -13,12 +13,14 @@ use rayon::prelude::*;
 /* mod declaration */
 pub mod annotation;
 pub mod annotations_db;
+pub mod cache;
 pub mod effect;
 pub mod error;
 pub mod memoizor;
 pub mod myth;
 pub mod output;
 pub mod sequences_db;
+pub mod stream;
 pub mod translate;
 pub mod variant;
 pub mod variant2myth;
@@ -35,31 +37,52 @@ where
     T: AsRef<[u8]>,
     S: serde::Serializer,
 {
-    serializer.serialize_str(unsafe { std::str::from_utf8_unchecked(v.as_ref()) })
+    // value could come from input files, invalid utf8 is replace
+    serializer.serialize_str(&String::from_utf8_lossy(v.as_ref()))
+}
+
+#[cfg(feature = "json")]
+fn serialize_vec_bstr<T, S>(v: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]>,
+    S: serde::Serializer,
+{
+    // value could come from input files, invalid utf8 is replace
+    serializer.collect_seq(v.iter().map(|x| String::from_utf8_lossy(x.as_ref())))
+}
+
+#[cfg(feature = "json")]
+fn serialize_map_bstr<K, V, S>(v: &[(K, V)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+    S: serde::Serializer,
+{
+    // gff3 attributes can contains any bytes, invalid utf8 is replace
+    serializer.collect_map(v.iter().map(|(k, v)| {
+        (
+            String::from_utf8_lossy(k.as_ref()),
+            String::from_utf8_lossy(v.as_ref()),
+        )
+    }))
 }
 
 /// For each variants found matching annotations
 #[cfg(not(feature = "parallel"))]
 pub fn vcf2myth<R>(
-    annotations: &annotations_db::AnnotationsDataBase,
-    sequences: &sequences_db::SequencesDataBase,
-    translate: &translate::Translate,
+    variant2myth: &variant2myth::Variant2Myth,
     vcf_reader: variant::VcfReader<R>,
-    annotators_choices: variant2myth::AnnotatorsChoices,
     mut writer: Box<dyn output::MythWriter>,
 ) -> error::Result<()>
 where
     R: std::io::BufRead,
 {
-    let variant2myth =
-        variant2myth::Variant2Myth::new(annotations, translate, sequences, annotators_choices);
+    writer.write_header(vcf_reader.header())?;
 
     for result in vcf_reader {
-        let variant = result?;
-
-        let myth = variant2myth.myth(variant);
-
-        writer.write_myth(myth)?;
+        if let Some(myth) = variant2myth.record(result)? {
+            writer.write_myth(myth)?;
+        }
     }
 
     writer.close()?;
@@ -68,46 +91,335 @@ where
 }
 
 /// For each variants found matching annotations
+///
+/// Variants are read by chunk, each chunk is annotate in parallel and send to writer thread in
+/// input order, number of chunk wait to be write is bounded.
 #[cfg(feature = "parallel")]
 pub fn vcf2myth<R>(
-    annotations: &annotations_db::AnnotationsDataBase,
-    sequences: &sequences_db::SequencesDataBase,
-    translate: &translate::Translate,
+    variant2myth: &variant2myth::Variant2Myth,
     vcf_reader: variant::VcfReader<R>,
-    annotators_choices: variant2myth::AnnotatorsChoices,
     mut writer: Box<dyn output::MythWriter + std::marker::Send>,
 ) -> error::Result<()>
 where
     R: std::io::BufRead + std::marker::Send,
 {
-    let (tx, rx) = std::sync::mpsc::channel::<myth::Myth>();
+    writer.write_header(vcf_reader.header())?;
+
+    let (tx, write_thread) = spawn_writer(writer);
+
+    let result = chunks2myth(variant2myth, vcf_reader, &tx);
+
+    // writer is always stop and join, annotation error is report before writer error
+    drop(tx);
+    let written = write_thread.join().unwrap(); // Err only if panic! if write thread panic all should panic !
+
+    result.and(written)
+}
+
+/// Number of variants annotate together
+#[cfg(feature = "parallel")]
+const CHUNK_SIZE: usize = 4096;
+
+/// Number of annotated chunks wait to be write
+#[cfg(feature = "parallel")]
+const CHANNEL_BOUND: usize = 4;
+
+/// Spawn a thread that write chunks of myth receive on channel
+#[cfg(feature = "parallel")]
+fn spawn_writer(
+    mut writer: Box<dyn output::MythWriter + std::marker::Send>,
+) -> (
+    std::sync::mpsc::SyncSender<Vec<myth::Myth>>,
+    std::thread::JoinHandle<error::Result<()>>,
+) {
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Vec<myth::Myth>>(CHANNEL_BOUND);
 
     let write_thread = std::thread::spawn(move || -> error::Result<()> {
-        for myth in rx {
-            writer.write_myth(myth)?;
+        for chunk in rx {
+            for myth in chunk {
+                writer.write_myth(myth)?;
+            }
         }
         writer.close()?;
         Ok(())
     });
 
-    let variant2myth =
-        variant2myth::Variant2Myth::new(annotations, translate, sequences, annotators_choices);
+    (tx, write_thread)
+}
 
-    let results = vcf_reader
-        .par_bridge()
-        .filter(Result::is_ok)
-        .map(error::Result::unwrap)
-        .map(|variant| tx.send(variant2myth.myth(variant)))
-        .filter(|r| r.is_err())
-        .collect::<Vec<core::result::Result<(), std::sync::mpsc::SendError<myth::Myth>>>>();
+/// Read variants by chunk, annotate each chunk in parallel and send it in input order
+#[cfg(feature = "parallel")]
+fn chunks2myth<I>(
+    variant2myth: &variant2myth::Variant2Myth,
+    results: I,
+    tx: &std::sync::mpsc::SyncSender<Vec<myth::Myth>>,
+) -> error::Result<()>
+where
+    I: Iterator<Item = error::Result<variant::Variant>>,
+{
+    let mut results = results.peekable();
+
+    while results.peek().is_some() {
+        let chunk = results
+            .by_ref()
+            .take(CHUNK_SIZE)
+            .collect::<Vec<error::Result<variant::Variant>>>();
+
+        let records = chunk
+            .into_par_iter()
+            .map(|result| variant2myth.record(result))
+            .collect::<Vec<error::Result<Option<myth::Myth>>>>();
+
+        // first error in input order is report
+        let mut myths = Vec::with_capacity(records.len());
+        for record in records {
+            myths.extend(record?);
+        }
+
+        // writer thread stop only on error, this error is report by join
+        if tx.send(myths).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// For each variants found matching annotations, databases are load by sequence.
+///
+/// Variants must be group by sequence, `load` is call once for each sequence and databases are
+/// drop before next sequence. `build` create a Variant2Myth from databases and translate.
+/// Return number of variants with a problem.
+#[cfg(not(feature = "parallel"))]
+pub fn vcf2myth_by_seqname<R, L, B>(
+    mut load: L,
+    translate: &translate::Translate,
+    build: B,
+    vcf_reader: variant::VcfReader<R>,
+    mut writer: Box<dyn output::MythWriter>,
+) -> error::Result<variant2myth::Counts>
+where
+    R: std::io::BufRead,
+    L: FnMut(
+        &[u8],
+    ) -> error::Result<(
+        annotations_db::AnnotationsDataBase,
+        sequences_db::SequencesDataBase,
+    )>,
+    B: for<'a> Fn(
+        &'a annotations_db::AnnotationsDataBase,
+        &'a translate::Translate,
+        &'a sequences_db::SequencesDataBase,
+    ) -> error::Result<variant2myth::Variant2Myth<'a>>,
+{
+    writer.write_header(vcf_reader.header())?;
+
+    let mut variants = vcf_reader.peekable();
+    let mut seqnames = ahash::AHashSet::new();
+    let mut counts = variant2myth::Counts::default();
+    while let Some((seqname, invalids)) = next_seqname(&mut variants, &mut seqnames)? {
+        let name = seqname_display(seqname.as_deref());
+        log::info!("Start annotate {}", name);
+        let (annotations, sequences) = seqname_databases(&mut load, seqname.as_deref())?;
+        let variant2myth = build(&annotations, translate, &sequences)?;
+
+        for result in invalids.into_iter().chain(seqname_variants(
+            &mut variants,
+            seqname.as_deref().unwrap_or_default(),
+        )) {
+            if let Some(myth) = variant2myth.record(result)? {
+                writer.write_myth(myth)?;
+            }
+        }
 
-    for result in results {
-        result?
+        counts += variant2myth.counts();
+        log::info!("End annotate {}", name);
     }
 
+    writer.close()?;
+
+    Ok(counts)
+}
+
+/// For each variants found matching annotations, databases are load by sequence.
+///
+/// Variants must be group by sequence, `load` is call once for each sequence and databases are
+/// drop before next sequence. `build` create a Variant2Myth from databases and translate.
+/// Return number of variants with a problem.
+#[cfg(feature = "parallel")]
+pub fn vcf2myth_by_seqname<R, L, B>(
+    mut load: L,
+    translate: &translate::Translate,
+    build: B,
+    vcf_reader: variant::VcfReader<R>,
+    mut writer: Box<dyn output::MythWriter + std::marker::Send>,
+) -> error::Result<variant2myth::Counts>
+where
+    R: std::io::BufRead,
+    L: FnMut(
+        &[u8],
+    ) -> error::Result<(
+        annotations_db::AnnotationsDataBase,
+        sequences_db::SequencesDataBase,
+    )>,
+    B: for<'a> Fn(
+        &'a annotations_db::AnnotationsDataBase,
+        &'a translate::Translate,
+        &'a sequences_db::SequencesDataBase,
+    ) -> error::Result<variant2myth::Variant2Myth<'a>>,
+{
+    writer.write_header(vcf_reader.header())?;
+
+    let (tx, write_thread) = spawn_writer(writer);
+
+    let result = seqnames2myth(load, translate, build, vcf_reader, &tx);
+
+    // writer is always stop and join, annotation error is report before writer error
     drop(tx);
+    let written = write_thread.join().unwrap(); // Err only if panic! if write thread panic all should panic !
+
+    let counts = result?;
+    written?;
+
+    Ok(counts)
+}
+
+/// Load databases of each sequence, annotate its variants in parallel and send them to writer
+#[cfg(feature = "parallel")]
+fn seqnames2myth<R, L, B>(
+    mut load: L,
+    translate: &translate::Translate,
+    build: B,
+    vcf_reader: variant::VcfReader<R>,
+    tx: &std::sync::mpsc::SyncSender<Vec<myth::Myth>>,
+) -> error::Result<variant2myth::Counts>
+where
+    R: std::io::BufRead,
+    L: FnMut(
+        &[u8],
+    ) -> error::Result<(
+        annotations_db::AnnotationsDataBase,
+        sequences_db::SequencesDataBase,
+    )>,
+    B: for<'a> Fn(
+        &'a annotations_db::AnnotationsDataBase,
+        &'a translate::Translate,
+        &'a sequences_db::SequencesDataBase,
+    ) -> error::Result<variant2myth::Variant2Myth<'a>>,
+{
+    let mut variants = vcf_reader.peekable();
+    let mut seqnames = ahash::AHashSet::new();
+    let mut counts = variant2myth::Counts::default();
+    while let Some((seqname, invalids)) = next_seqname(&mut variants, &mut seqnames)? {
+        let name = seqname_display(seqname.as_deref());
+        log::info!("Start annotate {}", name);
+        let (annotations, sequences) = seqname_databases(&mut load, seqname.as_deref())?;
+        let variant2myth = build(&annotations, translate, &sequences)?;
+
+        chunks2myth(
+            &variant2myth,
+            invalids.into_iter().chain(seqname_variants(
+                &mut variants,
+                seqname.as_deref().unwrap_or_default(),
+            )),
+            tx,
+        )?;
+
+        counts += variant2myth.counts();
+        log::info!("End annotate {}", name);
+    }
+
+    Ok(counts)
+}
 
-    write_thread.join().unwrap() // Err only if panic! if write thread panic all should panic !
+/// Name of sequence in log, None is invalid records at end of vcf
+fn seqname_display(seqname: Option<&[u8]>) -> std::borrow::Cow<'_, str> {
+    seqname.map_or("invalid records".into(), String::from_utf8_lossy)
+}
+
+/// Load databases of sequence, if there is no sequence (only invalid records) `load` isn't call
+/// and databases are empty
+fn seqname_databases<L>(
+    load: &mut L,
+    seqname: Option<&[u8]>,
+) -> error::Result<(
+    annotations_db::AnnotationsDataBase,
+    sequences_db::SequencesDataBase,
+)>
+where
+    L: FnMut(
+        &[u8],
+    ) -> error::Result<(
+        annotations_db::AnnotationsDataBase,
+        sequences_db::SequencesDataBase,
+    )>,
+{
+    match seqname {
+        Some(seqname) => load(seqname),
+        None => Ok((
+            annotations_db::AnnotationsDataBase::from_records(vec![], 0)?,
+            sequences_db::SequencesDataBase::from_records(vec![])?,
+        )),
+    }
+}
+
+/// Get sequence of next variant, error if this sequence was already seen
+///
+/// Invalid records found before next variant are return with sequence, if there are only
+/// invalid records until end of vcf sequence is None.
+#[allow(clippy::type_complexity)]
+fn next_seqname<I>(
+    variants: &mut std::iter::Peekable<I>,
+    seqnames: &mut ahash::AHashSet<Vec<u8>>,
+) -> error::Result<Option<(Option<Vec<u8>>, Vec<error::Result<variant::Variant>>)>>
+where
+    I: Iterator<Item = error::Result<variant::Variant>>,
+{
+    let mut invalids = Vec::new();
+
+    let seqname = loop {
+        match variants.peek() {
+            None if invalids.is_empty() => return Ok(None),
+            None => return Ok(Some((None, invalids))),
+            Some(Ok(variant)) => break variant.seqname.clone(),
+            Some(Err(error)) => {
+                if !matches!(
+                    error.downcast_ref::<error::Error>(),
+                    Some(error::Error::VcfInvalidRecord { .. })
+                ) {
+                    // other error is return by next
+                    return variants.next().transpose().map(|_| None);
+                }
+
+                invalids.extend(variants.next());
+            }
+        }
+    };
+
+    if !seqnames.insert(seqname.clone()) {
+        return Err(
+            error::Error::UnsortedVariant(String::from_utf8_lossy(&seqname).to_string()).into(),
+        );
+    }
+
+    Ok(Some((Some(seqname), invalids)))
+}
+
+/// Iterate over next variants on seqname, parsing error are include
+fn seqname_variants<'a, I>(
+    variants: &'a mut std::iter::Peekable<I>,
+    seqname: &'a [u8],
+) -> impl Iterator<Item = error::Result<variant::Variant>> + 'a
+where
+    I: Iterator<Item = error::Result<variant::Variant>> + 'a,
+{
+    std::iter::from_fn(move || {
+        variants.next_if(|result| {
+            result
+                .as_ref()
+                .map_or(true, |variant| variant.seqname == seqname)
+        })
+    })
 }
 
 #[cfg(test)]
@@ -117,4 +429,49 @@ mod tests {
     /* crate use */
 
     /* project use */
+    use super::*;
+
+    fn invalid() -> error::Result<variant::Variant> {
+        Err(error::Error::VcfInvalidRecord {
+            line: 1,
+            record: "chrA".to_string(),
+            message: "missing columns".to_string(),
+        }
+        .into())
+    }
+
+    #[test]
+    fn next_seqname_invalids() -> error::Result<()> {
+        let mut variants = vec![
+            invalid(),
+            Ok(variant::Variant::test_variant(
+                b"chrA", 10, b"A", b"G", None,
+            )?),
+            invalid(),
+        ]
+        .into_iter()
+        .peekable();
+        let mut seqnames = ahash::AHashSet::new();
+
+        let (seqname, invalids) = next_seqname(&mut variants, &mut seqnames)?.unwrap();
+        assert_eq!(seqname, Some(b"chrA".to_vec()));
+        assert_eq!(invalids.len(), 1);
+        assert_eq!(seqname_variants(&mut variants, b"chrA").count(), 2);
+
+        // Only invalid records left, no sequence to load
+        let mut variants = vec![invalid(), invalid()].into_iter().peekable();
+        let (seqname, invalids) = next_seqname(&mut variants, &mut seqnames)?.unwrap();
+        assert_eq!(seqname, None);
+        assert_eq!(invalids.len(), 2);
+        assert!(next_seqname(&mut variants, &mut seqnames)?.is_none());
+
+        let mut load = |_: &[u8]| -> error::Result<(
+            annotations_db::AnnotationsDataBase,
+            sequences_db::SequencesDataBase,
+        )> { unreachable!("load is never call") };
+        let (annotations, _) = seqname_databases(&mut load, None)?;
+        assert!(annotations.get_annotations(b"chrA", 0..100).is_empty());
+
+        Ok(())
+    }
 }
@@ -11,12 +11,17 @@ use clap::Parser as _;
 
 /* project use */
 use variant_myth::annotations_db;
+use variant_myth::cache;
 use variant_myth::cli;
 use variant_myth::error;
+use variant_myth::output;
 use variant_myth::sequences_db;
+use variant_myth::stream;
 use variant_myth::translate;
 use variant_myth::variant;
+use variant_myth::variant2myth;
 use variant_myth::vcf2myth;
+use variant_myth::vcf2myth_by_seqname;
 
 fn main() -> error::Result<()> {
     // parse cli
@@ -36,26 +41,273 @@ fn main() -> error::Result<()> {
         .num_threads(params.threads())
         .build_global()?;
 
+    if let cli::OutputSubCommand::Index(index) = &params.output {
+        return write_cache(&params, index);
+    }
+
+    params.variant_paths()?;
+    params.splice_window()?;
+
+    if params.stream() {
+        return stream(&params);
+    }
+
     let (annotations, sequences, translate) = get_database(&params)?;
 
-    for (input, output) in params.variant()?.iter_mut().zip(params.output.writers()?) {
-        log::info!("Start annotate variant");
-        let vcf_reader = variant::VcfReader::from_reader(input);
+    let transcript_selection = params.transcript_selection()?;
 
-        vcf2myth(
+    annotate_files(&params, |vcf_reader, writer| {
+        let variant2myth = build_variant2myth(
+            &params,
             &annotations,
-            &sequences,
             &translate,
-            vcf_reader,
-            params.annotators_choices(),
-            output,
+            &sequences,
+            transcript_selection.clone(),
         )?;
-        log::info!("End annotate variant");
+
+        vcf2myth(&variant2myth, vcf_reader, writer)?;
+
+        Ok(variant2myth.counts())
+    })
+}
+
+type Reader = std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>;
+type Writer = Box<dyn output::MythWriter + std::marker::Send>;
+
+fn annotate_file<F>(
+    params: &cli::Command,
+    input: &std::path::PathBuf,
+    output: &std::path::Path,
+    annotate: &F,
+) -> error::Result<variant2myth::Counts>
+where
+    F: Fn(variant::VcfReader<Reader>, Writer) -> error::Result<variant2myth::Counts>,
+{
+    log::info!("Start annotate variant {}", input.display());
+    let start = std::time::Instant::now();
+
+    let vcf_reader = variant::VcfReader::from_reader(params.variant_reader(input)?);
+    let writer = params.output.writer(output, &params.attributes())?;
+
+    let counts = annotate(vcf_reader, writer)
+        .with_context(|| format!("Annotate variant {}", input.display()))?;
+
+    log::debug!(
+        "Annotate variant {} take {:.2?}",
+        input.display(),
+        start.elapsed()
+    );
+    log::info!(
+        "End annotate variant {}, {} variants",
+        input.display(),
+        counts.variants
+    );
+
+    Ok(counts)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn annotate_files<F>(params: &cli::Command, annotate: F) -> error::Result<()>
+where
+    F: Fn(variant::VcfReader<Reader>, Writer) -> error::Result<variant2myth::Counts>,
+{
+    let counts = params
+        .variant_paths()?
+        .iter()
+        .zip(params.output.paths())
+        .map(|(input, output)| annotate_file(params, input, output, &annotate))
+        .collect::<error::Result<Vec<variant2myth::Counts>>>()?;
+
+    log_summary(counts);
+
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+fn annotate_files<F>(params: &cli::Command, annotate: F) -> error::Result<()>
+where
+    F: Fn(variant::VcfReader<Reader>, Writer) -> error::Result<variant2myth::Counts>
+        + std::marker::Sync,
+{
+    use rayon::prelude::*;
+
+    let counts = params
+        .variant_paths()?
+        .par_iter()
+        .zip(params.output.paths())
+        .map(|(input, output)| annotate_file(params, input, output, &annotate))
+        .collect::<error::Result<Vec<variant2myth::Counts>>>()?;
+
+    log_summary(counts);
+
+    Ok(())
+}
+
+fn log_summary(counts: Vec<variant2myth::Counts>) {
+    let files = counts.len();
+    let total = counts
+        .into_iter()
+        .fold(variant2myth::Counts::default(), |mut acc, counts| {
+            acc += counts;
+            acc
+        });
+
+    log::info!("Annotate {} variants from {} files", total.variants, files);
+    log_counts(total);
+}
+
+fn build_variant2myth<'a>(
+    params: &cli::Command,
+    annotations: &'a annotations_db::AnnotationsDataBase,
+    translate: &'a translate::Translate,
+    sequences: &'a sequences_db::SequencesDataBase,
+    transcript_selection: variant2myth::TranscriptSelection,
+) -> error::Result<variant2myth::Variant2Myth<'a>> {
+    Ok(variant2myth::Variant2Myth::new(
+        annotations,
+        translate,
+        sequences,
+        params.annotators_choices(),
+    )
+    .splice_window(params.splice_window()?)
+    .normalize(params.normalize())
+    .ref_mismatch(params.ref_mismatch())
+    .bad_record(params.bad_record())
+    .transcript_selection(transcript_selection)
+    .attributes(params.attributes()))
+}
+
+fn log_counts(counts: variant2myth::Counts) {
+    if counts.ref_mismatch != 0 {
+        log::warn!(
+            "{} variants with REF that didn't match reference genome",
+            counts.ref_mismatch
+        );
     }
 
+    if counts.bad_record != 0 {
+        log::warn!("{} vcf records can't be parse", counts.bad_record);
+    }
+}
+
+fn stream(params: &cli::Command) -> error::Result<()> {
+    let translate = read_translate(params)?;
+    let transcript_selection = params.transcript_selection()?;
+
+    // indexed reference is open once and share by all files and sequences
+    let indexed = params
+        .indexed_reference()
+        .map(|(path, cache_blocks)| {
+            sequences_db::SequencesDataBase::from_indexed_path(path, cache_blocks)
+                .with_context(|| format!("Read indexed reference {}", path.display()))
+        })
+        .transpose()?;
+
+    annotate_files(params, |vcf_reader, writer| {
+        // each file is read one time if its sequences are in vcf order
+        let mut annotations_records =
+            stream::BySeqname::new(|| Ok(annotations_db::records(params.annotations()?)))?;
+
+        // without indexed reference fasta is read by sequence
+        let mut sequences_records = indexed
+            .is_none()
+            .then(|| stream::BySeqname::new(|| Ok(sequences_db::records(params.reference()?))))
+            .transpose()?;
+
+        let load = |seqname: &[u8]| {
+            log::info!("Start read genome reference");
+            let sequences = match (&indexed, sequences_records.as_mut()) {
+                (Some(indexed), _) => indexed.clone(),
+                (None, Some(records)) => {
+                    sequences_db::SequencesDataBase::from_records(records.get(seqname)?)?
+                }
+                (None, None) => unreachable!("fasta is read by sequence without indexed reference"),
+            };
+            log::info!("End read genome reference");
+
+            log::info!("Start read annotations");
+            let annotations = annotations_db::AnnotationsDataBase::from_records(
+                annotations_records.get(seqname)?,
+                params.updown_distance(),
+            )?;
+            log::info!("End read annotations");
+
+            Ok((annotations, sequences))
+        };
+
+        vcf2myth_by_seqname(
+            load,
+            &translate,
+            |annotations, translate, sequences| {
+                build_variant2myth(
+                    params,
+                    annotations,
+                    translate,
+                    sequences,
+                    transcript_selection.clone(),
+                )
+            },
+            vcf_reader,
+            writer,
+        )
+    })
+}
+
+fn read_translate(params: &cli::Command) -> error::Result<translate::Translate> {
+    log::info!("Start read translation table");
+    let translate = if let Some(reader) = params.translate()? {
+        translate::Translate::from_reader(reader)?
+    } else {
+        translate::Translate::default()
+    };
+    log::info!("End read translation table");
+
+    Ok(translate)
+}
+
+fn write_cache(params: &cli::Command, index: &cli::Index) -> error::Result<()> {
+    log::info!("Start read genome reference");
+    let sequences = sequences_db::InMemory::from_reader(params.reference()?)?;
+    log::info!("End read genome reference");
+
+    log::info!("Start read annotations");
+    let annotations = annotations_db::RawAnnotations::from_reader(
+        params.annotations()?,
+        params.updown_distance(),
+    )?;
+    log::info!("End read annotations");
+
+    log::info!("Start write cache");
+    cache::write(
+        index.writer()?,
+        &params.cache_header(true)?,
+        &annotations,
+        &sequences,
+    )?;
+    log::info!("End write cache");
+
     Ok(())
 }
 
+fn read_cache(
+    params: &cli::Command,
+    path: &std::path::Path,
+) -> error::Result<(
+    annotations_db::AnnotationsDataBase,
+    sequences_db::SequencesDataBase,
+    translate::Translate,
+)> {
+    log::info!("Start read cache");
+    let verify = params.verify_cache();
+    let (annotations, sequences) = cache::read(path, &params.cache_header(verify)?, verify)
+        .with_context(|| format!("Read cache {}", path.display()))?;
+    log::info!("End read cache");
+
+    let translate = read_translate(params)?;
+
+    Ok((annotations, sequences, translate))
+}
+
 #[cfg(not(feature = "parallel"))]
 #[inline(always)]
 fn get_database(
@@ -65,8 +317,17 @@ fn get_database(
     sequences_db::SequencesDataBase,
     translate::Translate,
 )> {
+    if let Some(path) = params.cache_path() {
+        return read_cache(params, path);
+    }
+
     log::info!("Start read genome reference");
-    let sequences = sequences_db::SequencesDataBase::from_reader(params.reference()?)?;
+    let sequences = if let Some((path, cache_blocks)) = params.indexed_reference() {
+        sequences_db::SequencesDataBase::from_indexed_path(path, cache_blocks)
+            .with_context(|| format!("Read indexed reference {}", path.display()))?
+    } else {
+        sequences_db::SequencesDataBase::from_reader(params.reference()?)?
+    };
     log::info!("End read genome reference");
 
     log::info!("Start read annotations");
@@ -76,13 +337,7 @@ fn get_database(
     )?;
     log::info!("End read annotations");
 
-    log::info!("Start read translation table");
-    let translate = if let Some(reader) = params.translate()? {
-        translate::Translate::from_reader(reader)?
-    } else {
-        translate::Translate::default()
-    };
-    log::info!("End read translation table");
+    let translate = read_translate(params)?;
 
     Ok((annotations, sequences, translate))
 }
@@ -96,14 +351,30 @@ fn get_database(
     sequences_db::SequencesDataBase,
     translate::Translate,
 )> {
-    let seq_reader = params.reference()?;
-    let seq_thread = std::thread::spawn(|| {
-        log::info!("Start read genome reference");
-        let sequences = sequences_db::SequencesDataBase::from_reader(seq_reader)?;
-        log::info!("End read genome reference");
+    if let Some(path) = params.cache_path() {
+        return read_cache(params, path);
+    }
 
-        Ok::<sequences_db::SequencesDataBase, anyhow::Error>(sequences)
-    });
+    let seq_thread = if let Some((path, cache_blocks)) = params.indexed_reference() {
+        let path = path.to_path_buf();
+        std::thread::spawn(move || {
+            log::info!("Start read genome reference");
+            let sequences = sequences_db::SequencesDataBase::from_indexed_path(&path, cache_blocks)
+                .with_context(|| format!("Read indexed reference {}", path.display()))?;
+            log::info!("End read genome reference");
+
+            Ok::<sequences_db::SequencesDataBase, anyhow::Error>(sequences)
+        })
+    } else {
+        let seq_reader = params.reference()?;
+        std::thread::spawn(|| {
+            log::info!("Start read genome reference");
+            let sequences = sequences_db::SequencesDataBase::from_reader(seq_reader)?;
+            log::info!("End read genome reference");
+
+            Ok::<sequences_db::SequencesDataBase, anyhow::Error>(sequences)
+        })
+    };
 
     let annot_reader = params.annotations()?;
     let updown_distance = params.updown_distance();
@@ -20,7 +20,10 @@ pub struct Memoizor<'a> {
 
     option_coding_annotation: Option<Option<&'a Vec<annotation::Annotation>>>,
     option_exons_annotation: Option<Vec<annotation::Annotation>>,
+    option_cds_annotation: Option<Vec<annotation::Annotation>>,
     option_transcript: Option<Option<&'a annotation::Annotation>>,
+    option_gene: Option<Option<&'a annotation::Annotation>>,
+    option_coding_interval: Option<Option<core::ops::Range<u64>>>,
     option_epissed: Option<Vec<u8>>,
     option_epissed_edit: Option<Vec<u8>>,
     option_coding: Option<Vec<u8>>,
@@ -42,7 +45,10 @@ impl<'a> Memoizor<'a> {
             not_coding_annotations,
             option_coding_annotation: None,
             option_exons_annotation: None,
+            option_cds_annotation: None,
             option_transcript: None,
+            option_gene: None,
+            option_coding_interval: None,
             option_epissed: None,
             option_epissed_edit: None,
             option_coding: None,
@@ -73,19 +79,49 @@ impl<'a> Memoizor<'a> {
     #[inline(always)]
     pub fn exons_annotation(&mut self) -> &[annotation::Annotation] {
         if self.option_exons_annotation.is_none() {
-            self.option_exons_annotation = Some(
-                self.coding_annotation()
-                    .unwrap()
-                    .iter()
-                    .filter(|a| a.get_feature() == b"exon")
-                    .cloned()
-                    .collect::<Vec<annotation::Annotation>>(),
-            );
+            let mut exons = self
+                .coding_annotation()
+                .map(|annotations| {
+                    annotations
+                        .iter()
+                        .filter(|a| a.get_feature() == b"exon")
+                        .cloned()
+                        .collect::<Vec<annotation::Annotation>>()
+                })
+                .unwrap_or_default();
+
+            // Sequence extraction expect exons sorted by genomic position
+            exons.sort_by_key(|a| a.get_start());
+
+            self.option_exons_annotation = Some(exons);
         }
 
         self.option_exons_annotation.as_ref().unwrap()
     }
 
+    /// Get CDS extract from annotation present in transcript
+    #[inline(always)]
+    pub fn cds_annotation(&mut self) -> &[annotation::Annotation] {
+        if self.option_cds_annotation.is_none() {
+            let mut cds = self
+                .coding_annotation()
+                .map(|annotations| {
+                    annotations
+                        .iter()
+                        .filter(|a| a.get_feature() == b"CDS")
+                        .cloned()
+                        .collect::<Vec<annotation::Annotation>>()
+                })
+                .unwrap_or_default();
+
+            cds.sort_by_key(|a| a.get_start());
+
+            self.option_cds_annotation = Some(cds);
+        }
+
+        self.option_cds_annotation.as_ref().unwrap()
+    }
+
     /// Get transcript annotation from id
     #[inline(always)]
     pub fn transcript(&mut self) -> Option<&annotation::Annotation> {
@@ -96,6 +132,115 @@ impl<'a> Memoizor<'a> {
         self.option_transcript.unwrap() // value isn't none we check it
     }
 
+    /// Get gene annotation, parent of transcript
+    #[inline(always)]
+    pub fn gene(&mut self) -> Option<&annotation::Annotation> {
+        if self.option_gene.is_none() {
+            let annotations_db = self.annotations_db;
+            self.option_gene = Some(
+                self.transcript()
+                    .and_then(|transcript| annotations_db.get_gene(transcript.get_parent())),
+            );
+        }
+
+        self.option_gene.unwrap() // value isn't none we check it
+    }
+
+    /// Get strand of transcript, forward if transcript isn't found
+    #[inline(always)]
+    pub fn strand(&mut self) -> annotation::Strand {
+        self.transcript()
+            .map(|x| x.get_strand())
+            .cloned()
+            .unwrap_or(annotation::Strand::Forward)
+    }
+
+    /// Get genomic interval cover by coding region of transcript, None if transcript isn't coding
+    ///
+    /// Coding region is cover by CDS, start_codon and stop_codon, if transcript didn't have CDS and one codon is missing coding region extend to end of exons
+    pub fn coding_interval(&mut self) -> Option<core::ops::Range<u64>> {
+        if self.option_coding_interval.is_none() {
+            let strand = self.strand();
+
+            let exons = self.exons_annotation();
+            let exons_start = exons.iter().map(|a| a.get_start()).min();
+            let exons_stop = exons.iter().map(|a| a.get_stop()).max();
+
+            let cds = self.cds_annotation();
+            let cds_interval = cds
+                .iter()
+                .map(|a| a.get_start())
+                .min()
+                .zip(cds.iter().map(|a| a.get_stop()).max())
+                .map(|(start, stop)| start..stop);
+
+            let (start_codon, stop_codon) = self.codons();
+
+            self.option_coding_interval =
+                Some(match (cds_interval, start_codon, stop_codon, strand) {
+                    (Some(cds), start, stop, _) => {
+                        Some([start, stop].into_iter().flatten().fold(cds, |acc, codon| {
+                            acc.start.min(codon.start)..acc.end.max(codon.end)
+                        }))
+                    }
+                    (None, None, None, _) => None,
+                    (None, Some(start), Some(stop), _) => {
+                        Some(start.start.min(stop.start)..start.end.max(stop.end))
+                    }
+                    (None, Some(start), None, annotation::Strand::Forward) => {
+                        exons_stop.map(|stop| start.start..stop)
+                    }
+                    (None, Some(start), None, annotation::Strand::Reverse) => {
+                        exons_start.map(|begin| begin..start.end)
+                    }
+                    (None, None, Some(stop), annotation::Strand::Forward) => {
+                        exons_start.map(|begin| begin..stop.end)
+                    }
+                    (None, None, Some(stop), annotation::Strand::Reverse) => {
+                        exons_stop.map(|end| stop.start..end)
+                    }
+                });
+        }
+
+        self.option_coding_interval.clone().unwrap() // value isn't none we check it
+    }
+
+    /// Get number of bases before first complete codon of coding region
+    ///
+    /// Phase of first CDS is used only if transcript didn't have start_codon (5' incomplete CDS)
+    pub fn coding_phase(&mut self) -> u64 {
+        if self.codons().0.is_some() {
+            return 0;
+        }
+
+        let strand = self.strand();
+        let cds = self.cds_annotation();
+        let first = match strand {
+            annotation::Strand::Forward => cds.first(),
+            annotation::Strand::Reverse => cds.iter().max_by_key(|a| a.get_stop()),
+        };
+
+        first.map(|a| a.get_frame().phase()).unwrap_or(0)
+    }
+
+    /// Get interval of start_codon and stop_codon of transcript
+    fn codons(&mut self) -> (Option<core::ops::Range<u64>>, Option<core::ops::Range<u64>>) {
+        if let Some(annotations) = self.coding_annotation() {
+            (
+                annotations
+                    .iter()
+                    .find(|a| a.get_feature() == b"start_codon")
+                    .map(|a| a.get_interval()),
+                annotations
+                    .iter()
+                    .find(|a| a.get_feature() == b"stop_codon")
+                    .map(|a| a.get_interval()),
+            )
+        } else {
+            (None, None)
+        }
+    }
+
     /// Get concatenation of sequence covered by annotations
     pub fn epissed(
         &mut self,
@@ -116,12 +261,12 @@ impl<'a> Memoizor<'a> {
         strand: annotation::Strand,
         variant: &variant::Variant,
     ) -> error::Result<&[u8]> {
-        if self.option_transcript.is_none() {
+        if self.option_epissed_edit.is_none() {
             self.option_epissed_edit =
                 Some(self.sequences.epissed_edit(annotations, strand, variant)?);
         }
 
-        Ok(self.option_epissed.as_ref().unwrap())
+        Ok(self.option_epissed_edit.as_ref().unwrap())
     }
 
     /// Get coding sequence covered by annotations
@@ -152,7 +297,7 @@ impl<'a> Memoizor<'a> {
         start_position: std::option::Option<u64>,
         stop_position: std::option::Option<u64>,
     ) -> error::Result<&[u8]> {
-        if self.option_transcript.is_none() {
+        if self.option_coding_edit.is_none() {
             self.option_coding_edit = Some(self.sequences.coding_edit(
                 annotations,
                 strand,
@@ -162,7 +307,55 @@ impl<'a> Memoizor<'a> {
             )?);
         }
 
-        Ok(self.option_coding.as_ref().unwrap())
+        Ok(self.option_coding_edit.as_ref().unwrap())
+    }
+
+    /// Get reference and edited coding sequence of transcript, None if transcript isn't coding
+    pub fn coding_sequences(
+        &mut self,
+        variant: &variant::Variant,
+    ) -> error::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let interval = if let Some(interval) = self.coding_interval() {
+            interval
+        } else {
+            return Ok(None);
+        };
+
+        let strand = self.strand();
+        let phase = self.coding_phase() as usize;
+        let mut exons = self.exons_annotation().to_vec();
+        if exons.is_empty() {
+            // Transcript without exon, CDS give structure of coding region
+            exons = self.cds_annotation().to_vec();
+        }
+        let proxy = exons.iter().collect::<Vec<&annotation::Annotation>>();
+
+        // No exon in associate annotation no coding sequence
+        if proxy.is_empty() {
+            return Ok(None);
+        }
+
+        // SequencesDataBase::coding take 1-based bounds in transcript orientation
+        let (start_position, stop_position) = match strand {
+            annotation::Strand::Forward => (Some(interval.start + 1), Some(interval.end + 1)),
+            annotation::Strand::Reverse => (Some(interval.end + 1), Some(interval.start + 1)),
+        };
+
+        // Skip bases before first complete codon
+        let coding = self
+            .coding(&proxy, strand, start_position, stop_position)?
+            .iter()
+            .skip(phase)
+            .copied()
+            .collect();
+        let coding_edit = self
+            .coding_edit(&proxy, strand, variant, start_position, stop_position)?
+            .iter()
+            .skip(phase)
+            .copied()
+            .collect();
+
+        Ok(Some((coding, coding_edit)))
     }
 }
 
@@ -207,6 +400,7 @@ mod tests {
             &test_data::GFF_ANNOTATION[3..8]
         );
         assert_eq!(memoizor.transcript(), Some(&test_data::GFF_ANNOTATION[1]));
+        assert_eq!(memoizor.gene(), Some(&test_data::GFF_ANNOTATION[0]));
 
         let exons_annotations = memoizor.exons_annotation().to_vec();
         let proxy = exons_annotations
@@ -228,4 +422,95 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn cds() -> error::Result<()> {
+        let gff: &[u8] = b"chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr1
+chrA\ttest\texon\t61\t261\t.\t+\t.\tID=exon1;Parent=tr1
+chrA\ttest\tCDS\t100\t200\t.\t+\t1\tID=cds1;Parent=tr1
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr2
+chrA\ttest\tCDS\t100\t200\t.\t+\t0\tID=cds2;Parent=tr2
+chrA\ttest\tstop_codon\t201\t203\t.\t+\t0\tID=stop2;Parent=tr2
+";
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(gff));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+        let variant = variant::Variant::test_variant(b"chrA", 10, b"A", b"G", None)?;
+
+        // 5' incomplete CDS phase is used
+        let mut memoizor = Memoizor::new(b"tr1", &annotations_db, &test_data::SEQUENCE_DB, &[]);
+        assert_eq!(memoizor.cds_annotation().len(), 1);
+        assert_eq!(memoizor.coding_interval(), Some(99..200));
+        assert_eq!(memoizor.coding_phase(), 1);
+        let reference = test_data::SEQUENCE_DB
+            .get_interval(b"chrA", &(100..200))?
+            .to_vec();
+        assert_eq!(
+            memoizor.coding_sequences(&variant)?,
+            Some((reference.clone(), reference))
+        );
+
+        // CDS without exon, stop codon extend coding interval
+        let mut memoizor = Memoizor::new(b"tr2", &annotations_db, &test_data::SEQUENCE_DB, &[]);
+        assert!(memoizor.exons_annotation().is_empty());
+        assert_eq!(memoizor.coding_interval(), Some(99..203));
+        assert_eq!(memoizor.coding_phase(), 0);
+        assert!(memoizor.coding_sequences(&variant)?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn ensembl() -> error::Result<()> {
+        let gff: &[u8] = b"chrA\ttest\tgene\t51\t300\t.\t+\t.\tID=gene:g1;Name=G1
+chrA\ttest\tmRNA\t51\t300\t.\t+\t.\tID=transcript:tr1;Parent=gene:g1;biotype=protein_coding
+chrA\ttest\texon\t61\t261\t.\t+\t.\tParent=transcript:tr1
+chrA\ttest\tCDS\t100\t200\t.\t+\t0\tID=CDS:p1;Parent=transcript:tr1
+chrA\ttest\tncRNA_gene\t400\t500\t.\t-\t.\tID=gene:g2
+chrA\ttest\tlnc_RNA\t400\t500\t.\t-\t.\tID=transcript:tr2;Parent=gene:g2
+chrA\ttest\texon\t400\t500\t.\t-\t.\tParent=transcript:tr2
+";
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(gff));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 10)?;
+
+        // mRNA is a transcript, without start and stop codon
+        let mut memoizor = Memoizor::new(
+            b"transcript:tr1",
+            &annotations_db,
+            &test_data::SEQUENCE_DB,
+            &[],
+        );
+        assert_eq!(
+            memoizor.transcript().map(|a| a.get_feature().to_vec()),
+            Some(b"mRNA".to_vec())
+        );
+        assert_eq!(
+            memoizor.gene().map(|a| a.get_attribute().get_id().to_vec()),
+            Some(b"gene:g1".to_vec())
+        );
+        assert_eq!(memoizor.exons_annotation().len(), 1);
+        assert_eq!(memoizor.cds_annotation().len(), 1);
+        assert_eq!(memoizor.coding_interval(), Some(99..200));
+        assert_eq!(memoizor.coding_phase(), 0);
+
+        // lnc_RNA and ncRNA_gene are transcript and gene
+        let mut memoizor = Memoizor::new(
+            b"transcript:tr2",
+            &annotations_db,
+            &test_data::SEQUENCE_DB,
+            &[],
+        );
+        assert!(memoizor.transcript().is_some());
+        assert!(memoizor.gene().is_some());
+        assert!(memoizor.cds_annotation().is_empty());
+
+        // Upstream of mRNA is annotated
+        assert!(annotations_db
+            .get_annotations(b"chrA", 45..46)
+            .iter()
+            .any(|a| a.get_feature() == b"upstream" && a.get_parent() == b"transcript:tr1"));
+
+        Ok(())
+    }
 }
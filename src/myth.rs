@@ -31,14 +31,113 @@ pub struct AnnotationMyth {
     /// Feature name
     pub name: Vec<u8>,
 
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// Id of gene associate to feature
+    pub gene_id: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// Name of gene associate to feature
+    pub gene_name: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(feature = "json", serde(skip))]
+    /// Biotype of transcript, only write in vcf ANN field
+    pub biotype: Vec<u8>,
+
     /// Store effect of this variants
     pub effects: Vec<effect::Effect>,
 
     #[builder(private, default)]
     /// Store impact of effect
     pub impact: effect::Impact,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// HGVS transcript notation, c. for coding transcript n. for non coding transcript
+    pub hgvs_c: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// HGVS protein notation
+    pub hgvs_p: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// HGVS genomic notation
+    pub hgvs_g: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// Rank of exons affected by variant over number of exons
+    pub exon_rank: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// Warning about annotation quality
+    pub warning: Vec<u8>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_map_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    /// Value of attributes copy from transcript or gene annotation
+    pub attributes: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
+/// Attribute keys where transcript biotype is search, in order
+pub const BIOTYPE_KEYS: &[&[u8]] = &[b"biotype", b"transcript_biotype", b"transcript_type"];
+
+/// Warning set when variant reference sequence didn't match genome reference
+pub const WARNING_REF_MISMATCH: &[u8] = b"WARNING_REF_DOES_NOT_MATCH_GENOME";
+
 impl AnnotationMyth {
     /// Get builder of AnnotationMyth
     pub fn builder() -> AnnotationMythBuilder {
@@ -52,6 +151,13 @@ impl AnnotationMyth {
             .feature(annotation.get_feature().to_vec())
             .id(annotation.get_attribute().get_id().to_vec())
             .name(annotation.get_attribute().get_name().to_vec())
+            .biotype(
+                BIOTYPE_KEYS
+                    .iter()
+                    .find_map(|key| annotation.get_attribute().get(key))
+                    .unwrap_or_default()
+                    .to_vec(),
+            )
     }
 
     /// Create a preset builder of AnnotationMyth without annotation
@@ -79,6 +185,29 @@ impl AnnotationMythBuilder {
         }
     }
 
+    /// Set gene information in AnnotationMyth
+    pub fn set_gene(&mut self, gene_id: Vec<u8>, gene_name: Vec<u8>) {
+        self.gene_id = Some(gene_id);
+        self.gene_name = Some(gene_name);
+    }
+
+    /// Set HGVS notations in AnnotationMyth
+    pub fn set_hgvs(&mut self, hgvs_c: Vec<u8>, hgvs_p: Vec<u8>, hgvs_g: Vec<u8>) {
+        self.hgvs_c = Some(hgvs_c);
+        self.hgvs_p = Some(hgvs_p);
+        self.hgvs_g = Some(hgvs_g);
+    }
+
+    /// Set rank of exons affected by variant in AnnotationMyth
+    pub fn set_exon_rank(&mut self, exon_rank: Vec<u8>) {
+        self.exon_rank = Some(exon_rank);
+    }
+
+    /// Set attributes copy from annotation in AnnotationMyth
+    pub fn set_attributes(&mut self, attributes: Vec<(Vec<u8>, Vec<u8>)>) {
+        self.attributes = Some(attributes);
+    }
+
     /// Extend Effect in AnnotationMyth
     pub fn extend_effect(&mut self, e: &[effect::Effect]) {
         if let Some(effects) = &mut self.effects {
@@ -164,9 +293,18 @@ mod tests {
                 source: b"test".to_vec(),
                 feature: b"gene".to_vec(),
                 name: b"gene1".to_vec(),
+                gene_id: vec![],
+                gene_name: vec![],
+                biotype: vec![],
                 id: b"11111".to_vec(),
                 effects: vec![effect::Effect::GeneVariant, effect::Effect::ExonRegion],
                 impact: effect::Impact::Modifier,
+                hgvs_c: vec![],
+                hgvs_p: vec![],
+                hgvs_g: vec![],
+                exon_rank: vec![],
+                warning: vec![],
+                attributes: vec![],
             }
         );
 
@@ -184,9 +322,18 @@ mod tests {
                 source: b"test".to_vec(),
                 feature: b"gene".to_vec(),
                 name: b"gene1".to_vec(),
+                gene_id: vec![],
+                gene_name: vec![],
+                biotype: vec![],
                 id: b"1111".to_vec(),
                 effects: vec![effect::Effect::GeneVariant, effect::Effect::ExonRegion],
                 impact: effect::Impact::Modifier,
+                hgvs_c: vec![],
+                hgvs_p: vec![],
+                hgvs_g: vec![],
+                exon_rank: vec![],
+                warning: vec![],
+                attributes: vec![],
             }
         )
     }
@@ -204,9 +351,18 @@ mod tests {
                 source: b"HAVANA".to_vec(),
                 feature: b"transcript".to_vec(),
                 name: b"transcript_name".to_vec(),
+                gene_id: vec![],
+                gene_name: vec![],
+                biotype: b"lncRNA".to_vec(),
                 id: b"ENST00000797271.1".to_vec(),
                 effects: vec![effect::Effect::ExonRegion],
                 impact: effect::Impact::Modifier,
+                hgvs_c: vec![],
+                hgvs_p: vec![],
+                hgvs_g: vec![],
+                exon_rank: vec![],
+                warning: vec![],
+                attributes: vec![],
             }
         );
 
@@ -224,9 +380,18 @@ mod tests {
                 source: b"variant_myth".to_vec(),
                 feature: b"unknow".to_vec(),
                 name: b"".to_vec(),
+                gene_id: vec![],
+                gene_name: vec![],
+                biotype: vec![],
                 id: b"".to_vec(),
                 effects: vec![effect::Effect::Ignore],
                 impact: effect::Impact::Other,
+                hgvs_c: vec![],
+                hgvs_p: vec![],
+                hgvs_g: vec![],
+                exon_rank: vec![],
+                warning: vec![],
+                attributes: vec![],
             }
         );
 
@@ -250,6 +415,17 @@ mod tests {
             ref_seq: b"T".to_vec(),
             alt_seq: b".".to_vec(),
             variant_type: variant::Type::Small,
+            id: b".".to_vec(),
+            quality: b".".to_vec(),
+            filter: b".".to_vec(),
+            info: b".".to_vec(),
+            format: vec![],
+            samples: vec![],
+            allele_index: 0,
+            allele_count: 1,
+            alts: b".".to_vec(),
+            original: None,
+            confidence: (0, 0),
         });
 
         myth.add_annotation(annotation.build().unwrap());
@@ -263,14 +439,34 @@ mod tests {
                     ref_seq: b"T".to_vec(),
                     alt_seq: b".".to_vec(),
                     variant_type: variant::Type::Small,
+                    id: b".".to_vec(),
+                    quality: b".".to_vec(),
+                    filter: b".".to_vec(),
+                    info: b".".to_vec(),
+                    format: vec![],
+                    samples: vec![],
+                    allele_index: 0,
+                    allele_count: 1,
+                    alts: b".".to_vec(),
+                    original: None,
+                    confidence: (0, 0),
                 },
                 annotations: vec![AnnotationMyth {
                     source: b"test".to_vec(),
                     feature: b"gene".to_vec(),
                     name: b"gene1".to_vec(),
+                    gene_id: vec![],
+                    gene_name: vec![],
+                    biotype: vec![],
                     id: b"1111".to_vec(),
                     effects: vec![effect::Effect::GeneVariant, effect::Effect::ExonRegion],
-                    impact: effect::Impact::Modifier
+                    impact: effect::Impact::Modifier,
+                    hgvs_c: vec![],
+                    hgvs_p: vec![],
+                    hgvs_g: vec![],
+                    exon_rank: vec![],
+                    warning: vec![],
+                    attributes: vec![],
                 }]
             }
         );
@@ -9,6 +9,8 @@
 mod json;
 #[cfg(feature = "parquet")]
 mod parquet;
+#[cfg(feature = "vcf")]
+mod vcf;
 
 /* project use */
 use crate::error;
@@ -21,6 +23,8 @@ pub use json::JsonFormat;
 pub use json::JsonWriter;
 #[cfg(feature = "parquet")]
 pub use parquet::ParquetWriter;
+#[cfg(feature = "vcf")]
+pub use vcf::VcfWriter;
 
 /// Common metadata to all output
 pub fn get_metadata() -> Vec<(&'static str, &'static str)> {
@@ -29,6 +33,22 @@ pub fn get_metadata() -> Vec<(&'static str, &'static str)> {
         ("pos", "position of variant"),
         ("ref", "reference sequence"),
         ("alt", "alternative sequence"),
+        (
+            "allele_index",
+            "index of alternative sequence in original vcf ALT column (0-based)",
+        ),
+        ("variant_id", "identifiant of variant, same as original vcf"),
+        ("quality", "quality of variant, same as original vcf"),
+        ("filter", "filter status of variant, same as original vcf"),
+        (
+            "info",
+            "additional information of variant, same as original vcf",
+        ),
+        ("format", "genotype format of variant, same as original vcf"),
+        (
+            "samples",
+            "genotype value of each sample, same as original vcf",
+        ),
         ("source", "source of variant in gff3 file"),
         (
             "feature",
@@ -39,11 +59,28 @@ pub fn get_metadata() -> Vec<(&'static str, &'static str)> {
             "name of feature, same value of Name gff3 attributes",
         ),
         ("id", "id of feature, same value of Id gff3 attributes"),
+        ("gene_id", "id of gene associate to feature"),
+        ("gene_name", "name of gene associate to feature"),
         ("effect", "List of sequence ontology terms"),
         (
             "impact",
             "0: UNKOWN, 1:LOW, 2:MODIFIER, 3: MODERATE, 4:HIGH",
         ),
+        (
+            "hgvs_c",
+            "HGVS notation of variant on transcript (c. or n.)",
+        ),
+        ("hgvs_p", "HGVS notation of variant on protein (p.)"),
+        ("hgvs_g", "HGVS notation of variant on genome (g.)"),
+        (
+            "exon_rank",
+            "rank of exons affected by variant / number of exons",
+        ),
+        ("warning", "warning about annotation quality"),
+        (
+            "attributes",
+            "value of selected gff3 attributes of transcript or gene",
+        ),
     ]
 }
 
@@ -54,6 +91,12 @@ pub fn get_metadata() -> Vec<(&'static str, &'static str)> {
 /// [`write_batch`]: ./fn.write_batch.html
 /// [`close`]: ./fn.close.html
 pub trait MythWriter {
+    /// This method is called with header lines of input vcf before any Myth are write.
+    /// Default implementation ignore header.
+    fn write_header(&mut self, _header: &[Vec<u8>]) -> error::Result<()> {
+        Ok(())
+    }
+
     /// This method is called for each variant for which a Myth object was found.
     /// Do not implement this method!
     fn write_myth(&mut self, myth: myth::Myth) -> error::Result<()> {
@@ -137,6 +137,17 @@ mod tests {
             ref_seq: b"T".to_vec(),
             alt_seq: b".".to_vec(),
             variant_type: variant::Type::Small,
+            id: b".".to_vec(),
+            quality: b".".to_vec(),
+            filter: b".".to_vec(),
+            info: b".".to_vec(),
+            format: vec![],
+            samples: vec![],
+            allele_index: 0,
+            allele_count: 1,
+            alts: b".".to_vec(),
+            original: None,
+            confidence: (0, 0),
         });
         myth.add_annotation(annotation.build().unwrap());
 
@@ -148,6 +159,7 @@ mod tests {
 
         annotation2.add_effect(effect::Effect::DisruptiveInframeDeletion);
         annotation2.add_effect(effect::Effect::ExonRegion);
+        annotation2.set_attributes(vec![(b"gene_type".to_vec(), b"lncRNA".to_vec())]);
 
         let mut myth2 = Myth::from_variant(variant::Variant {
             seqname: b"21".to_vec(),
@@ -155,6 +167,17 @@ mod tests {
             ref_seq: b"C".to_vec(),
             alt_seq: b"T".to_vec(),
             variant_type: variant::Type::Small,
+            id: b".".to_vec(),
+            quality: b".".to_vec(),
+            filter: b".".to_vec(),
+            info: b".".to_vec(),
+            format: vec![],
+            samples: vec![],
+            allele_index: 0,
+            allele_count: 1,
+            alts: b"T".to_vec(),
+            original: None,
+            confidence: (0, 0),
         });
 
         myth2.add_annotation(annotation2.build().unwrap());
@@ -181,16 +204,31 @@ mod tests {
             r#"{
 "metadata":
 {
+  "allele_index": "index of alternative sequence in original vcf ALT column (0-based)",
   "alt": "alternative sequence",
+  "attributes": "value of selected gff3 attributes of transcript or gene",
   "chr": "chromosome name same ase original vcf",
   "effect": "List of sequence ontology terms",
+  "exon_rank": "rank of exons affected by variant / number of exons",
   "feature": "type of feature affected by variant gene/transcript",
+  "filter": "filter status of variant, same as original vcf",
+  "format": "genotype format of variant, same as original vcf",
+  "gene_id": "id of gene associate to feature",
+  "gene_name": "name of gene associate to feature",
+  "hgvs_c": "HGVS notation of variant on transcript (c. or n.)",
+  "hgvs_g": "HGVS notation of variant on genome (g.)",
+  "hgvs_p": "HGVS notation of variant on protein (p.)",
   "id": "id of feature, same value of Id gff3 attributes",
   "impact": "0: UNKOWN, 1:LOW, 2:MODIFIER, 3: MODERATE, 4:HIGH",
+  "info": "additional information of variant, same as original vcf",
   "name": "name of feature, same value of Name gff3 attributes",
   "pos": "position of variant",
+  "quality": "quality of variant, same as original vcf",
   "ref": "reference sequence",
-  "source": "source of variant in gff3 file"
+  "samples": "genotype value of each sample, same as original vcf",
+  "source": "source of variant in gff3 file",
+  "variant_id": "identifiant of variant, same as original vcf",
+  "warning": "warning about annotation quality"
 },
 "variants": [
 {
@@ -198,7 +236,12 @@ mod tests {
     "seqname": "93",
     "position": 2036067340,
     "ref_seq": "T",
-    "alt_seq": "."
+    "alt_seq": ".",
+    "id": ".",
+    "quality": ".",
+    "filter": ".",
+    "info": ".",
+    "allele_index": 0
   },
   "myth": [
     {
@@ -219,7 +262,12 @@ mod tests {
     "seqname": "21",
     "position": 1970,
     "ref_seq": "C",
-    "alt_seq": "T"
+    "alt_seq": "T",
+    "id": ".",
+    "quality": ".",
+    "filter": ".",
+    "info": ".",
+    "allele_index": 0
   },
   "myth": [
     {
@@ -231,7 +279,10 @@ mod tests {
         "DisruptiveInframeDeletion",
         "ExonRegion"
       ],
-      "impact": "Moderate"
+      "impact": "Moderate",
+      "attributes": {
+        "gene_type": "lncRNA"
+      }
     }
   ]
 }]
@@ -253,9 +304,29 @@ mod tests {
 
         assert_eq!(
             std::str::from_utf8(&annot_writer.output_stream).unwrap(),
-            r#"{"metadata":{"alt":"alternative sequence","chr":"chromosome name same ase original vcf","effect":"List of sequence ontology terms","feature":"type of feature affected by variant gene/transcript","id":"id of feature, same value of Id gff3 attributes","impact":"0: UNKOWN, 1:LOW, 2:MODIFIER, 3: MODERATE, 4:HIGH","name":"name of feature, same value of Name gff3 attributes","pos":"position of variant","ref":"reference sequence","source":"source of variant in gff3 file"}}
-{"variant":{"seqname":"93","position":2036067340,"ref_seq":"T","alt_seq":"."},"myth":[{"source":"test","feature":"gene","id":"1111","name":"gene1","effects":["GeneVariant","ExonRegion"],"impact":"Modifier"}]}
-{"variant":{"seqname":"21","position":1970,"ref_seq":"C","alt_seq":"T"},"myth":[{"source":"test2","feature":"gene2","id":"7777","name":"gene51","effects":["DisruptiveInframeDeletion","ExonRegion"],"impact":"Moderate"}]}"#
+            r#"{"metadata":{"allele_index":"index of alternative sequence in original vcf ALT column (0-based)","alt":"alternative sequence","attributes":"value of selected gff3 attributes of transcript or gene","chr":"chromosome name same ase original vcf","effect":"List of sequence ontology terms","exon_rank":"rank of exons affected by variant / number of exons","feature":"type of feature affected by variant gene/transcript","filter":"filter status of variant, same as original vcf","format":"genotype format of variant, same as original vcf","gene_id":"id of gene associate to feature","gene_name":"name of gene associate to feature","hgvs_c":"HGVS notation of variant on transcript (c. or n.)","hgvs_g":"HGVS notation of variant on genome (g.)","hgvs_p":"HGVS notation of variant on protein (p.)","id":"id of feature, same value of Id gff3 attributes","impact":"0: UNKOWN, 1:LOW, 2:MODIFIER, 3: MODERATE, 4:HIGH","info":"additional information of variant, same as original vcf","name":"name of feature, same value of Name gff3 attributes","pos":"position of variant","quality":"quality of variant, same as original vcf","ref":"reference sequence","samples":"genotype value of each sample, same as original vcf","source":"source of variant in gff3 file","variant_id":"identifiant of variant, same as original vcf","warning":"warning about annotation quality"}}
+{"variant":{"seqname":"93","position":2036067340,"ref_seq":"T","alt_seq":".","id":".","quality":".","filter":".","info":".","allele_index":0},"myth":[{"source":"test","feature":"gene","id":"1111","name":"gene1","effects":["GeneVariant","ExonRegion"],"impact":"Modifier"}]}
+{"variant":{"seqname":"21","position":1970,"ref_seq":"C","alt_seq":"T","id":".","quality":".","filter":".","info":".","allele_index":0},"myth":[{"source":"test2","feature":"gene2","id":"7777","name":"gene51","effects":["DisruptiveInframeDeletion","ExonRegion"],"impact":"Moderate","attributes":{"gene_type":"lncRNA"}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_write_invalid_utf8_attribute() {
+        use crate::effect;
+        use crate::myth::AnnotationMyth;
+
+        let mut annotation = AnnotationMyth::builder()
+            .source(b"test".to_vec())
+            .feature(b"transcript".to_vec())
+            .id(b"tr1".to_vec());
+        annotation.add_effect(effect::Effect::GeneVariant);
+        annotation.set_attributes(vec![(b"key\xff".to_vec(), b"tr\xff%\xff\xfe".to_vec())]);
+
+        let value = serde_json::to_value(annotation.build().unwrap()).unwrap();
+
+        assert_eq!(
+            value["attributes"],
+            serde_json::json!({"key\u{FFFD}": "tr\u{FFFD}%\u{FFFD}\u{FFFD}"})
         );
     }
 }
@@ -9,6 +9,7 @@ use crate::effect;
 use crate::error;
 use crate::myth;
 use crate::output;
+use crate::variant;
 
 fn get_metadata() -> Vec<parquet::file::metadata::KeyValue> {
     crate::output::get_metadata()
@@ -19,13 +20,20 @@ fn get_metadata() -> Vec<parquet::file::metadata::KeyValue> {
         .collect()
 }
 
-/// Get schema of parquet output
-pub fn schema() -> arrow::datatypes::Schema {
+/// Get schema of parquet output, each attribute key add a column attribute_{key}
+pub fn schema(attribute_keys: &[Vec<u8>]) -> arrow::datatypes::Schema {
     let mut fields = vec![
         arrow::datatypes::Field::new("chr", arrow::datatypes::DataType::Utf8, false),
         arrow::datatypes::Field::new("pos", arrow::datatypes::DataType::UInt64, false),
         arrow::datatypes::Field::new("ref", arrow::datatypes::DataType::Utf8, false),
         arrow::datatypes::Field::new("alt", arrow::datatypes::DataType::Utf8, false),
+        arrow::datatypes::Field::new("allele_index", arrow::datatypes::DataType::UInt64, false),
+        arrow::datatypes::Field::new("variant_id", arrow::datatypes::DataType::Utf8, false),
+        arrow::datatypes::Field::new("quality", arrow::datatypes::DataType::Utf8, false),
+        arrow::datatypes::Field::new("filter", arrow::datatypes::DataType::Utf8, false),
+        arrow::datatypes::Field::new("info", arrow::datatypes::DataType::Utf8, false),
+        arrow::datatypes::Field::new("format", arrow::datatypes::DataType::Utf8, false),
+        arrow::datatypes::Field::new("samples", arrow::datatypes::DataType::Utf8, false),
     ];
 
     fields.extend(vec![
@@ -33,10 +41,25 @@ pub fn schema() -> arrow::datatypes::Schema {
         arrow::datatypes::Field::new("feature", arrow::datatypes::DataType::Utf8, true),
         arrow::datatypes::Field::new("name", arrow::datatypes::DataType::Utf8, true),
         arrow::datatypes::Field::new("id", arrow::datatypes::DataType::Utf8, true),
+        arrow::datatypes::Field::new("gene_id", arrow::datatypes::DataType::Utf8, true),
+        arrow::datatypes::Field::new("gene_name", arrow::datatypes::DataType::Utf8, true),
         arrow::datatypes::Field::new("effects", arrow::datatypes::DataType::Utf8, true),
         arrow::datatypes::Field::new("impact", arrow::datatypes::DataType::UInt8, true),
+        arrow::datatypes::Field::new("hgvs_c", arrow::datatypes::DataType::Utf8, true),
+        arrow::datatypes::Field::new("hgvs_p", arrow::datatypes::DataType::Utf8, true),
+        arrow::datatypes::Field::new("hgvs_g", arrow::datatypes::DataType::Utf8, true),
+        arrow::datatypes::Field::new("exon_rank", arrow::datatypes::DataType::Utf8, true),
+        arrow::datatypes::Field::new("warning", arrow::datatypes::DataType::Utf8, true),
     ]);
 
+    fields.extend(attribute_keys.iter().map(|key| {
+        arrow::datatypes::Field::new(
+            format!("attribute_{}", String::from_utf8_lossy(key)),
+            arrow::datatypes::DataType::Utf8,
+            true,
+        )
+    }));
+
     arrow::datatypes::Schema::new(fields)
 }
 
@@ -48,19 +71,35 @@ pub struct ParquetWriter<W: std::io::Write + std::marker::Send + std::io::Seek +
     poss: Vec<u64>,
     refs: Vec<String>,
     alts: Vec<String>,
+    allele_indexes: Vec<u64>,
+    variant_ids: Vec<String>,
+    qualities: Vec<String>,
+    filters: Vec<String>,
+    infos: Vec<String>,
+    formats: Vec<String>,
+    samples: Vec<String>,
     source: Vec<String>,
     feature: Vec<String>,
     name: Vec<String>,
     id: Vec<String>,
+    gene_id: Vec<String>,
+    gene_name: Vec<String>,
     effects: Vec<String>,
     impact: Vec<u8>,
+    hgvs_c: Vec<String>,
+    hgvs_p: Vec<String>,
+    hgvs_g: Vec<String>,
+    exon_rank: Vec<String>,
+    warning: Vec<String>,
+    attribute_keys: Vec<Vec<u8>>,
+    attributes: Vec<Vec<String>>,
     block_size: usize,
 }
 
 impl<W: std::io::Write + Send + std::io::Seek + 'static> ParquetWriter<W> {
-    /// Create a new ParquetWriter
-    pub fn new(writer: W, block_size: usize) -> error::Result<Self> {
-        let schema = std::sync::Arc::new(schema());
+    /// Create a new ParquetWriter, value of attribute_keys are write in dedicated columns
+    pub fn new(writer: W, block_size: usize, attribute_keys: Vec<Vec<u8>>) -> error::Result<Self> {
+        let schema = std::sync::Arc::new(schema(&attribute_keys));
 
         let columns_metadata = get_metadata();
 
@@ -80,44 +119,92 @@ impl<W: std::io::Write + Send + std::io::Seek + 'static> ParquetWriter<W> {
             poss: Vec::with_capacity(block_size),
             refs: Vec::with_capacity(block_size),
             alts: Vec::with_capacity(block_size),
+            allele_indexes: Vec::with_capacity(block_size),
+            variant_ids: Vec::with_capacity(block_size),
+            qualities: Vec::with_capacity(block_size),
+            filters: Vec::with_capacity(block_size),
+            infos: Vec::with_capacity(block_size),
+            formats: Vec::with_capacity(block_size),
+            samples: Vec::with_capacity(block_size),
             source: Vec::with_capacity(block_size),
             feature: Vec::with_capacity(block_size),
             name: Vec::with_capacity(block_size),
             id: Vec::with_capacity(block_size),
+            gene_id: Vec::with_capacity(block_size),
+            gene_name: Vec::with_capacity(block_size),
             effects: Vec::with_capacity(block_size),
             impact: Vec::with_capacity(block_size),
+            hgvs_c: Vec::with_capacity(block_size),
+            hgvs_p: Vec::with_capacity(block_size),
+            hgvs_g: Vec::with_capacity(block_size),
+            exon_rank: Vec::with_capacity(block_size),
+            warning: Vec::with_capacity(block_size),
+            attributes: vec![Vec::with_capacity(block_size); attribute_keys.len()],
+            attribute_keys,
             block_size,
         })
     }
+
+    /// Add variant columns of one row, vcf columns could contains invalid utf8 it's replace
+    fn push_variant(&mut self, variant: &variant::Variant) {
+        self.chrs
+            .push(String::from_utf8_lossy(&variant.seqname).into_owned());
+        self.poss.push(variant.position);
+        self.refs
+            .push(String::from_utf8_lossy(&variant.ref_seq).into_owned());
+        self.alts
+            .push(String::from_utf8_lossy(&variant.alt_seq).into_owned());
+        self.allele_indexes.push(variant.allele_index as u64);
+        self.variant_ids
+            .push(String::from_utf8_lossy(&variant.id).into_owned());
+        self.qualities
+            .push(String::from_utf8_lossy(&variant.quality).into_owned());
+        self.filters
+            .push(String::from_utf8_lossy(&variant.filter).into_owned());
+        self.infos
+            .push(String::from_utf8_lossy(&variant.info).into_owned());
+        self.formats
+            .push(String::from_utf8_lossy(&variant.format).into_owned());
+        self.samples
+            .push(String::from_utf8_lossy(&variant.samples.join(&b'\t')).into_owned());
+    }
 }
 
 impl<W: std::io::Write + std::marker::Send + std::io::Seek + 'static> output::MythWriter
     for ParquetWriter<W>
 {
+    fn write_header(&mut self, header: &[Vec<u8>]) -> error::Result<()> {
+        self.writer
+            .append_key_value_metadata(parquet::file::metadata::KeyValue::new(
+                String::from("vcf_header"),
+                Some(String::from_utf8_lossy(&header.join(&b'\n')).into_owned()),
+            ));
+
+        Ok(())
+    }
+
     fn add_myth(&mut self, myth: myth::Myth) -> error::Result<()> {
         if myth.annotations.is_empty() {
-            self.chrs
-                .push(unsafe { String::from_utf8_unchecked(myth.variant.seqname.clone()) });
-            self.poss.push(myth.variant.position);
-            self.refs
-                .push(unsafe { String::from_utf8_unchecked(myth.variant.ref_seq.clone()) });
-            self.alts
-                .push(unsafe { String::from_utf8_unchecked(myth.variant.alt_seq.clone()) });
+            self.push_variant(&myth.variant);
             self.source.push("".to_string());
             self.feature.push("".to_string());
             self.name.push("".to_string());
             self.id.push("".to_string());
+            self.gene_id.push("".to_string());
+            self.gene_name.push("".to_string());
             self.effects.push("".to_string());
             self.impact.push(effect::Impact::Other as u8);
+            self.hgvs_c.push("".to_string());
+            self.hgvs_p.push("".to_string());
+            self.hgvs_g.push("".to_string());
+            self.exon_rank.push("".to_string());
+            self.warning.push("".to_string());
+            self.attributes
+                .iter_mut()
+                .for_each(|column| column.push("".to_string()));
         }
         for annotation in myth.annotations {
-            self.chrs
-                .push(unsafe { String::from_utf8_unchecked(myth.variant.seqname.clone()) });
-            self.poss.push(myth.variant.position);
-            self.refs
-                .push(unsafe { String::from_utf8_unchecked(myth.variant.ref_seq.clone()) });
-            self.alts
-                .push(unsafe { String::from_utf8_unchecked(myth.variant.alt_seq.clone()) });
+            self.push_variant(&myth.variant);
             self.source
                 .push(unsafe { String::from_utf8_unchecked(annotation.source) });
             self.feature
@@ -126,6 +213,10 @@ impl<W: std::io::Write + std::marker::Send + std::io::Seek + 'static> output::My
                 .push(unsafe { String::from_utf8_unchecked(annotation.name) });
             self.id
                 .push(unsafe { String::from_utf8_unchecked(annotation.id) });
+            self.gene_id
+                .push(unsafe { String::from_utf8_unchecked(annotation.gene_id) });
+            self.gene_name
+                .push(unsafe { String::from_utf8_unchecked(annotation.gene_name) });
             self.effects.push(
                 annotation
                     .effects
@@ -135,6 +226,26 @@ impl<W: std::io::Write + std::marker::Send + std::io::Seek + 'static> output::My
                     .join(";"),
             );
             self.impact.push(annotation.impact as u8);
+            self.hgvs_c
+                .push(unsafe { String::from_utf8_unchecked(annotation.hgvs_c) });
+            self.hgvs_p
+                .push(unsafe { String::from_utf8_unchecked(annotation.hgvs_p) });
+            self.hgvs_g
+                .push(unsafe { String::from_utf8_unchecked(annotation.hgvs_g) });
+            self.exon_rank
+                .push(unsafe { String::from_utf8_unchecked(annotation.exon_rank) });
+            self.warning
+                .push(unsafe { String::from_utf8_unchecked(annotation.warning) });
+            for (key, column) in self.attribute_keys.iter().zip(self.attributes.iter_mut()) {
+                column.push(
+                    annotation
+                        .attributes
+                        .iter()
+                        .find(|(k, _)| k == key)
+                        .map(|(_, v)| String::from_utf8_lossy(v).into_owned())
+                        .unwrap_or_default(),
+                );
+            }
         }
 
         Ok(())
@@ -148,41 +259,86 @@ impl<W: std::io::Write + std::marker::Send + std::io::Seek + 'static> output::My
         Ok(())
     }
     fn write_batch(&mut self) -> error::Result<()> {
-        let batch = arrow::record_batch::RecordBatch::try_new(
-            self.schema.clone(),
-            vec![
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.chrs,
-                ))),
-                std::sync::Arc::new(arrow::array::UInt64Array::from(std::mem::take(
-                    &mut self.poss,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.refs,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.alts,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.source,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.feature,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.name,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.id,
-                ))),
-                std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
-                    &mut self.effects,
-                ))),
-                std::sync::Arc::new(arrow::array::UInt8Array::from(std::mem::take(
-                    &mut self.impact,
-                ))),
-            ],
-        )?;
+        let mut columns: Vec<arrow::array::ArrayRef> = vec![
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.chrs,
+            ))),
+            std::sync::Arc::new(arrow::array::UInt64Array::from(std::mem::take(
+                &mut self.poss,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.refs,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.alts,
+            ))),
+            std::sync::Arc::new(arrow::array::UInt64Array::from(std::mem::take(
+                &mut self.allele_indexes,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.variant_ids,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.qualities,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.filters,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.infos,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.formats,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.samples,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.source,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.feature,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.name,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.id,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.gene_id,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.gene_name,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.effects,
+            ))),
+            std::sync::Arc::new(arrow::array::UInt8Array::from(std::mem::take(
+                &mut self.impact,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.hgvs_c,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.hgvs_p,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.hgvs_g,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.exon_rank,
+            ))),
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(
+                &mut self.warning,
+            ))),
+        ];
+        columns.extend(self.attributes.iter_mut().map(|column| {
+            std::sync::Arc::new(arrow::array::StringArray::from(std::mem::take(column)))
+                as arrow::array::ArrayRef
+        }));
+
+        let batch = arrow::record_batch::RecordBatch::try_new(self.schema.clone(), columns)?;
 
         self.writer.write(&batch)?;
         Ok(())
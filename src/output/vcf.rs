@@ -0,0 +1,386 @@
+//! The VCF writer module. Provides implementation for writing [`Myth`] objects in VCF, annotation are store in INFO field ANN.
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::effect;
+use crate::error;
+use crate::myth;
+use crate::output;
+
+const ANN_HEADER: &[u8] = b"##INFO=<ID=ANN,Number=.,Type=String,Description=\"Functional annotations: 'Allele | Annotation | Annotation_Impact | Gene_Name | Gene_ID | Feature_Type | Feature_ID | Transcript_BioType | Rank | HGVS.c | HGVS.p | cDNA.pos / cDNA.length | CDS.pos / CDS.length | AA.pos / AA.length | Distance | ERRORS / WARNINGS / INFO'\">";
+
+const DEFAULT_HEADER: &[&[u8]] = &[
+    b"##fileformat=VCFv4.2",
+    b"#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO",
+];
+
+/// Struct to write Myth in vcf format
+///
+/// Myth of alleles of a multi-allelic record are merged in one record, they must be add one after the other.
+pub struct VcfWriter<W> {
+    output_stream: W,
+    header_written: bool,
+    alleles: Vec<myth::Myth>,
+}
+
+impl<W: std::io::Write> VcfWriter<W> {
+    /// Create a new VcfWriter
+    pub fn new(output_stream: W) -> error::Result<Self> {
+        Ok(Self {
+            output_stream,
+            header_written: false,
+            alleles: Vec::new(),
+        })
+    }
+
+    /// Write one vcf record, Myth of each allele of record are merged
+    ///
+    /// ALT and samples columns are write as in input, allele without Myth have no ANN entry.
+    fn write_record(&mut self, mut myths: Vec<myth::Myth>) -> error::Result<()> {
+        myths.sort_by_key(|m| m.variant.allele_index);
+
+        let variant = &myths[0].variant;
+
+        let mut info = variant
+            .info
+            .split(|c| *c == b';')
+            .filter(|i| !i.is_empty() && *i != b"." && !i.starts_with(b"ANN="))
+            .map(|i| i.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+
+        let ann = myths
+            .iter()
+            .flat_map(|m| {
+                m.annotations
+                    .iter()
+                    .map(|a| annotation2ann(m.variant.vcf_allele().2, a))
+            })
+            .collect::<Vec<Vec<u8>>>();
+        if !ann.is_empty() {
+            let mut field = b"ANN=".to_vec();
+            field.extend(ann.join(&b','));
+            info.push(field);
+        }
+
+        let info = if info.is_empty() {
+            b".".to_vec()
+        } else {
+            info.join(&b';')
+        };
+
+        let (position, ref_seq, _) = variant.vcf_allele();
+        let position = (position + 1).to_string();
+        let mut columns: Vec<&[u8]> = vec![
+            &variant.seqname,
+            position.as_bytes(),
+            &variant.id,
+            ref_seq,
+            &variant.alts,
+            &variant.quality,
+            &variant.filter,
+            &info,
+        ];
+        if !variant.format.is_empty() {
+            columns.push(&variant.format);
+        }
+        columns.extend(variant.samples.iter().map(|s| s.as_slice()));
+
+        self.output_stream.write_all(&columns.join(&b'\t'))?;
+        self.output_stream.write_all(b"\n")?;
+
+        Ok(())
+    }
+
+    /// Write alleles wait to be merged
+    fn flush_alleles(&mut self) -> error::Result<()> {
+        if self.alleles.is_empty() {
+            return Ok(());
+        }
+
+        let myths = std::mem::take(&mut self.alleles);
+        self.write_record(myths)
+    }
+}
+
+/// Myth are alleles of the same vcf record
+fn same_record(a: &myth::Myth, b: &myth::Myth) -> bool {
+    a.variant.seqname == b.variant.seqname
+        && a.variant.vcf_allele().0 == b.variant.vcf_allele().0
+        && a.variant.vcf_allele().1 == b.variant.vcf_allele().1
+        && a.variant.id == b.variant.id
+        && a.variant.alts == b.variant.alts
+}
+
+/// Percent-encode character not allowed in ANN sub field
+fn escape(value: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(value.len());
+
+    for c in value {
+        match c {
+            b'%' | b',' | b';' | b'|' | b'=' | b' ' | b'\t' => {
+                escaped.extend(format!("%{:02X}", c).as_bytes())
+            }
+            _ => escaped.push(*c),
+        }
+    }
+
+    escaped
+}
+
+/// Impact name in SnpEff ANN format
+fn impact2ann(impact: &effect::Impact) -> &'static [u8] {
+    match impact {
+        effect::Impact::High => b"HIGH",
+        effect::Impact::Moderate => b"MODERATE",
+        effect::Impact::Low => b"LOW",
+        effect::Impact::Modifier | effect::Impact::Other => b"MODIFIER",
+    }
+}
+
+/// Build one ANN entry from an AnnotationMyth
+///
+/// cDNA.pos / cDNA.length, CDS.pos / CDS.length, AA.pos / AA.length and Distance are left empty on purpose,
+/// positions are available in HGVS.c and HGVS.p and distance to feature isn't compute.
+fn annotation2ann(allele: &[u8], annotation: &myth::AnnotationMyth) -> Vec<u8> {
+    let effects = annotation
+        .effects
+        .iter()
+        .map(|e| e.clone().into())
+        .collect::<Vec<Vec<u8>>>()
+        .join(&b'&');
+
+    let fields: [&[u8]; 16] = [
+        allele,
+        &effects,
+        impact2ann(&annotation.impact),
+        &annotation.gene_name,
+        &annotation.gene_id,
+        &annotation.feature,
+        &annotation.id,
+        &annotation.biotype,
+        &annotation.exon_rank,
+        &annotation.hgvs_c,
+        &annotation.hgvs_p,
+        b"",
+        b"",
+        b"",
+        b"",
+        &annotation.warning,
+    ];
+
+    fields
+        .iter()
+        .map(|f| escape(f))
+        .collect::<Vec<Vec<u8>>>()
+        .join(&b'|')
+}
+
+impl<W: std::io::Write> output::MythWriter for VcfWriter<W> {
+    fn write_header(&mut self, header: &[Vec<u8>]) -> error::Result<()> {
+        let header = if header.iter().any(|l| l.starts_with(b"#CHROM")) {
+            header.to_vec()
+        } else {
+            DEFAULT_HEADER.iter().map(|l| l.to_vec()).collect()
+        };
+
+        for line in header {
+            if line.starts_with(b"##INFO=<ID=ANN,") {
+                continue;
+            }
+
+            if line.starts_with(b"#CHROM") {
+                self.output_stream.write_all(ANN_HEADER)?;
+                self.output_stream.write_all(b"\n")?;
+            }
+
+            self.output_stream.write_all(&line)?;
+            self.output_stream.write_all(b"\n")?;
+        }
+
+        self.header_written = true;
+
+        Ok(())
+    }
+
+    fn add_myth(&mut self, myth: myth::Myth) -> error::Result<()> {
+        if !self.header_written {
+            self.write_header(&[])?;
+        }
+
+        // Some alleles of previous record never arrive (skipped), it's write as is
+        let new_record = self.alleles.first().is_some_and(|first| {
+            !same_record(first, &myth)
+                || self
+                    .alleles
+                    .iter()
+                    .any(|m| m.variant.allele_index == myth.variant.allele_index)
+        });
+        if new_record {
+            self.flush_alleles()?;
+        }
+
+        let allele_count = myth.variant.allele_count;
+        self.alleles.push(myth);
+
+        if self.alleles.len() >= allele_count {
+            self.flush_alleles()?;
+        }
+
+        Ok(())
+    }
+
+    fn batch_full(&self) -> bool {
+        false
+    }
+
+    fn finalize(&mut self) -> error::Result<()> {
+        if !self.header_written {
+            self.write_header(&[])?;
+        }
+
+        self.flush_alleles()?;
+
+        self.output_stream.flush()?;
+        Ok(())
+    }
+
+    fn write_batch(&mut self) -> error::Result<()> {
+        log::debug!("Calling write_batch on VcfWriter makes no sense!");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use super::*;
+    use crate::output::MythWriter as _;
+    use crate::variant;
+
+    #[test]
+    fn write() -> error::Result<()> {
+        let mut writer = VcfWriter::new(Vec::new())?;
+
+        writer.write_header(&[
+            b"##fileformat=VCFv4.3".to_vec(),
+            b"##INFO=<ID=ANN,Number=.,Type=String,Description=\"old\">".to_vec(),
+            b"#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample".to_vec(),
+        ])?;
+
+        let mut annotation = myth::AnnotationMyth::builder()
+            .source(b"test".to_vec())
+            .feature(b"transcript".to_vec())
+            .name(b"".to_vec())
+            .id(b"tr1".to_vec())
+            .biotype(b"protein_coding".to_vec());
+        annotation.add_effect(effect::Effect::MissenseVariant);
+        annotation.set_gene(b"g1".to_vec(), b"gene 1;a=b,c|d%".to_vec());
+        annotation.set_hgvs(b"tr1:c.4A>G".to_vec(), b"tr1:p.Lys2Glu".to_vec(), vec![]);
+
+        let mut variant = variant::Variant::test_variant(b"chr1", 10, b"A", b"G", None)?;
+        variant.id = b"rs1".to_vec();
+        variant.quality = b"50".to_vec();
+        variant.filter = b"PASS".to_vec();
+        variant.info = b"DP=10;ANN=old".to_vec();
+        variant.format = b"GT".to_vec();
+        variant.samples = vec![b"0/1".to_vec()];
+
+        let mut myth = myth::Myth::from_variant(variant);
+        myth.add_annotation(annotation.build()?);
+
+        writer.write_myth(myth)?;
+        writer.write_myth(myth::Myth::from_variant(variant::Variant::test_variant(
+            b"chr1", 20, b"T", b"C", None,
+        )?))?;
+        let mut variants = variant::Variant::from_byte_record(csv::ByteRecord::from(vec![
+            "chr1", "31", ".", "A", "C,T", "10", ".", ".",
+        ]))?;
+        let mut annotation = myth::AnnotationMyth::builder()
+            .source(b"test".to_vec())
+            .feature(b"transcript".to_vec())
+            .id(b"tr1".to_vec());
+        annotation.add_effect(effect::Effect::MissenseVariant);
+        let mut second = myth::Myth::from_variant(variants.pop().unwrap());
+        second.add_annotation(annotation.build()?);
+        writer.write_myth(second)?;
+        writer.write_myth(myth::Myth::from_variant(variants.pop().unwrap()))?;
+
+        writer.close()?;
+
+        assert_eq!(
+            String::from_utf8(writer.output_stream).unwrap(),
+            format!(
+                "##fileformat=VCFv4.3
+{}
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample
+chr1\t11\trs1\tA\tG\t50\tPASS\tDP=10;ANN=G|missense_variant|MODERATE|gene%201%3Ba%3Db%2Cc%7Cd%25|g1|transcript|tr1|protein_coding||tr1:c.4A>G|tr1:p.Lys2Glu|||||\tGT\t0/1
+chr1\t21\t.\tT\tC\t.\t.\t.
+chr1\t31\t.\tA\tC,T\t10\t.\tANN=T|missense_variant|MODERATE|||transcript|tr1|||||||||
+",
+                String::from_utf8(ANN_HEADER.to_vec()).unwrap()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_missing_allele() -> error::Result<()> {
+        let mut writer = VcfWriter::new(Vec::new())?;
+
+        writer.write_header(&[
+            b"##fileformat=VCFv4.3".to_vec(),
+            b"#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample".to_vec(),
+        ])?;
+
+        let mut variants = variant::Variant::from_byte_record(csv::ByteRecord::from(vec![
+            "chr1", "31", ".", "A", "C,T,G", ".", ".", ".", "GT", "1/3",
+        ]))?;
+        let mut annotation = myth::AnnotationMyth::builder()
+            .source(b"test".to_vec())
+            .feature(b"transcript".to_vec())
+            .id(b"tr1".to_vec());
+        annotation.add_effect(effect::Effect::MissenseVariant);
+        let mut second = myth::Myth::from_variant(variants.remove(1));
+        second.add_annotation(annotation.build()?);
+        writer.write_myth(second)?;
+
+        writer.write_myth(myth::Myth::from_variant(variant::Variant::test_variant(
+            b"chr1", 40, b"T", b"C", None,
+        )?))?;
+
+        // Same record twice, second one is missing an allele
+        for _ in 0..2 {
+            let mut variants = variant::Variant::from_byte_record(csv::ByteRecord::from(vec![
+                "chr1", "51", ".", "A", "C,T", ".", ".", ".",
+            ]))?;
+            writer.write_myth(myth::Myth::from_variant(variants.remove(0)))?;
+        }
+
+        writer.close()?;
+
+        assert_eq!(
+            String::from_utf8(writer.output_stream).unwrap(),
+            format!(
+                "##fileformat=VCFv4.3
+{}
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample
+chr1\t31\t.\tA\tC,T,G\t.\t.\tANN=T|missense_variant|MODERATE|||transcript|tr1|||||||||\tGT\t1/3
+chr1\t41\t.\tT\tC\t.\t.\t.
+chr1\t51\t.\tA\tC,T\t.\t.\t.
+chr1\t51\t.\tA\tC,T\t.\t.\t.
+",
+                String::from_utf8(ANN_HEADER.to_vec()).unwrap()
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -4,11 +4,19 @@
 
 /* crate use */
 
+/* module declaration */
+mod indexed;
+mod mapped;
+
 /* project use */
 use crate::annotation;
 use crate::error;
 use crate::variant;
 
+/* reexport */
+pub use indexed::Indexed;
+pub use mapped::Mapped;
+
 /// Perform reverse complement
 pub fn rev_comp(seq: &mut [u8]) {
     // Reverse the sequence
@@ -24,18 +32,33 @@ pub fn rev_comp(seq: &mut [u8]) {
     });
 }
 
-/// Store sequence data
-pub struct SequencesDataBase(ahash::AHashMap<Vec<u8>, Vec<u8>>);
+/// Source of reference sequence
+pub trait SequenceSource {
+    /// Get sequence of interval, borrowed if source keep it in memory
+    fn fetch(
+        &self,
+        seqname: &[u8],
+        interval: &core::ops::Range<u64>,
+    ) -> error::Result<std::borrow::Cow<'_, [u8]>>;
+}
+
+/// All reference sequence are store in memory
+pub struct InMemory(pub(crate) ahash::AHashMap<Vec<u8>, Vec<u8>>);
 
-impl SequencesDataBase {
-    /// Build a SequenceDataBase from a reader
+impl InMemory {
+    /// Read all sequence of a fasta reader
     pub fn from_reader(
         input: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>,
     ) -> error::Result<Self> {
-        let mut inner = ahash::AHashMap::new();
+        Self::from_records(records(input).map(|result| result.map(|(_, record)| record)))
+    }
 
-        let mut reader = noodles::fasta::io::Reader::new(input);
-        let records = reader.records();
+    /// Store sequence of fasta records
+    pub fn from_records<I>(records: I) -> error::Result<Self>
+    where
+        I: IntoIterator<Item = error::Result<noodles::fasta::Record>>,
+    {
+        let mut inner = ahash::AHashMap::new();
 
         for result in records {
             let record = result?;
@@ -44,24 +67,81 @@ impl SequencesDataBase {
 
         Ok(Self(inner))
     }
+}
+
+/// Iterate over records of fasta file with their sequence name
+pub fn records(
+    input: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>,
+) -> impl Iterator<Item = error::Result<(Vec<u8>, noodles::fasta::Record)>> {
+    let mut reader = noodles::fasta::io::Reader::new(input);
+
+    std::iter::from_fn(move || reader.records().next()).map(|result| {
+        let record = result?;
+        Ok((record.name().to_vec(), record))
+    })
+}
+
+impl SequenceSource for InMemory {
+    fn fetch(
+        &self,
+        seqname: &[u8],
+        interval: &core::ops::Range<u64>,
+    ) -> error::Result<std::borrow::Cow<'_, [u8]>> {
+        Ok(std::borrow::Cow::Borrowed(
+            self.0
+                .get(seqname)
+                .ok_or(error::Error::SeqNotInReferences(unsafe {
+                    String::from_utf8_unchecked(seqname.to_vec())
+                }))?
+                .get((interval.start as usize)..(interval.end as usize))
+                .ok_or(error::Error::IntervalNotInSeq {
+                    interval: interval.clone(),
+                    name: unsafe { String::from_utf8_unchecked(seqname.to_vec()) },
+                })?,
+        ))
+    }
+}
+
+/// Store sequence data, clone share the same source
+#[derive(Clone)]
+pub struct SequencesDataBase(
+    std::sync::Arc<dyn SequenceSource + std::marker::Send + std::marker::Sync>,
+);
+
+impl SequencesDataBase {
+    /// Build a SequenceDataBase from a source
+    pub fn new(
+        source: impl SequenceSource + std::marker::Send + std::marker::Sync + 'static,
+    ) -> Self {
+        Self(std::sync::Arc::new(source))
+    }
+
+    /// Build a SequenceDataBase from a reader, all sequence are load in memory
+    pub fn from_reader(
+        input: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>>,
+    ) -> error::Result<Self> {
+        Ok(Self::new(InMemory::from_reader(input)?))
+    }
+
+    /// Build a SequenceDataBase from fasta records, sequence are load in memory
+    pub fn from_records(records: Vec<noodles::fasta::Record>) -> error::Result<Self> {
+        Ok(Self::new(InMemory::from_records(
+            records.into_iter().map(Ok),
+        )?))
+    }
+
+    /// Build a SequenceDataBase from a fasta with a fai index, sequence are read on demand
+    pub fn from_indexed_path(path: &std::path::Path, cache_blocks: usize) -> error::Result<Self> {
+        Ok(Self::new(Indexed::from_path(path, cache_blocks)?))
+    }
 
     /// Get interval
     pub fn get_interval(
         &self,
         seqname: &[u8],
         interval: &core::ops::Range<u64>,
-    ) -> error::Result<&[u8]> {
-        Ok(self
-            .0
-            .get(seqname)
-            .ok_or(error::Error::SeqNotInReferences(unsafe {
-                String::from_utf8_unchecked(seqname.to_vec())
-            }))?
-            .get((interval.start as usize)..(interval.end as usize))
-            .ok_or(error::Error::IntervalNotInSeq {
-                interval: interval.clone(),
-                name: unsafe { String::from_utf8_unchecked(seqname.to_vec()) },
-            })?)
+    ) -> error::Result<std::borrow::Cow<'_, [u8]>> {
+        self.0.fetch(seqname, interval)
     }
 
     /// Get concatenation of sequence covered by annotations
@@ -75,7 +155,7 @@ impl SequencesDataBase {
 
             let mut result = Vec::new();
             for annotation in annotations {
-                result.extend(self.get_interval(seqname, &annotation.get_interval())?);
+                result.extend_from_slice(&self.get_interval(seqname, &annotation.get_interval())?);
             }
 
             if strand == annotation::Strand::Reverse {
@@ -119,6 +199,11 @@ impl SequencesDataBase {
             }
         }
 
+        // Variant isn't in annotations sequence isn't edit
+        if edit.is_empty() {
+            edit = epissed;
+        }
+
         if strand == annotation::Strand::Reverse {
             rev_comp(&mut edit);
         }
@@ -230,31 +315,35 @@ impl SequencesDataBase {
                 && stop > annotation.get_start()
             {
                 // start stop in same exon
-                result.extend(self.get_interval(seqname, &(start..stop))?);
-                if start < variant_pos && stop > variant_pos {
+                result.extend_from_slice(&self.get_interval(seqname, &(start..stop))?);
+                if start <= variant_pos && stop > variant_pos {
                     variant_pos -= start;
                     in_coding = true;
                 }
                 break;
             } else if start < annotation.get_stop() && start > annotation.get_start() {
                 // start exon
-                result.extend(self.get_interval(seqname, &(start..annotation.get_stop()))?);
-                if start < variant_pos && annotation.get_stop() > variant_pos {
+                result.extend_from_slice(
+                    &self.get_interval(seqname, &(start..annotation.get_stop()))?,
+                );
+                if start <= variant_pos && annotation.get_stop() > variant_pos {
                     variant_pos -= start;
                     in_coding = true;
                 }
             } else if stop < annotation.get_stop() && stop > annotation.get_start() {
                 // stop exon
-                result.extend(self.get_interval(seqname, &(annotation.get_start()..stop))?);
-                if annotation.get_start() < variant_pos && stop > variant_pos {
+                result.extend_from_slice(
+                    &self.get_interval(seqname, &(annotation.get_start()..stop))?,
+                );
+                if annotation.get_start() <= variant_pos && stop > variant_pos {
                     variant_pos -= annotation.get_start();
                     in_coding = true;
                 }
                 break;
             } else {
                 // all other case
-                result.extend(self.get_interval(seqname, &annotation.get_interval())?);
-                if annotation.get_start() < variant_pos && annotation.get_stop() > variant_pos {
+                result.extend_from_slice(&self.get_interval(seqname, &annotation.get_interval())?);
+                if annotation.get_start() <= variant_pos && annotation.get_stop() > variant_pos {
                     variant_pos -= annotation.get_start();
                     in_coding = true;
                 }
@@ -306,7 +395,7 @@ mod tests {
     fn get_interval() -> error::Result<()> {
         assert_eq!(
             b"TTCAATTTCAATATACGAAGgtaggcactgagatttcaat",
-            test_data::SEQUENCE_DB.get_interval(b"chrA", &(241..281))?
+            &*test_data::SEQUENCE_DB.get_interval(b"chrA", &(241..281))?
         );
 
         Ok(())
@@ -434,7 +523,7 @@ mod tests {
         let start_reverse = Some(13340);
         let stop_reverse = Some(220);
 
-        let forward_before = variant::Variant::test_variant(b"sequence", 62, b"G", b"ggg", None)?;
+        let forward_before = variant::Variant::test_variant(b"sequence", 61, b"G", b"ggg", None)?;
         assert_eq!(
             b"CTGACTTAAGAAGGAACTCAACGCAGAGGAAAGCAAAATGGAGACATGGAGGGAGACGCCAAGTTCCAGTGACATTAAGCCCCTGAATCCCACCATGGCTGAACTTGCATTACTGAAGCCCTCCTGAGTTGAATTTCTGCCTCTTGCAAATGAAAGAGGCCTGATGAATACCCACATAGTTCAATTTCAATATACGAAGTTCTTCAGACGACGGTCCCTGAGTTACTGAAGCCACTTCACCTGTTTGGGCAGACAGCTGGGAGTGCCCAGAGCTGACACCCTCCAGGTGACCCACAGGTAACGGCTGACCCACGCTGGAGT".to_vec(),
             test_data::SEQUENCE_DB.coding_edit(
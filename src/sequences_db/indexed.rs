@@ -0,0 +1,428 @@
+//! Reference sequence read on demand with a fasta index
+
+/* std use */
+use std::io::Read as _;
+use std::io::Seek as _;
+
+/* crate use */
+
+/* project use */
+use crate::error;
+use crate::sequences_db;
+
+/// Number of bases in a cached block
+const BLOCK_SIZE: u64 = 1 << 16;
+
+/// Position of a sequence in fasta file, read from fai index
+struct FaiRecord {
+    line: usize,
+    length: u64,
+    offset: u64,
+    line_bases: u64,
+    line_width: u64,
+}
+
+impl FaiRecord {
+    /// Byte offset in fasta file of base at position
+    fn byte_of(&self, position: u64) -> u64 {
+        self.offset + (position / self.line_bases) * self.line_width + position % self.line_bases
+    }
+}
+
+/// Read fai index, one line by sequence: name, length, offset, line bases, line width
+fn read_fai(input: impl std::io::BufRead) -> error::Result<ahash::AHashMap<Vec<u8>, FaiRecord>> {
+    let mut records = ahash::AHashMap::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields = line.split('\t').collect::<Vec<&str>>();
+        let parse = |i: usize| -> error::Result<u64> {
+            fields
+                .get(i)
+                .and_then(|f| f.parse::<u64>().ok())
+                .ok_or(error::Error::FaiBadRecord(index + 1).into())
+        };
+
+        let record = FaiRecord {
+            line: index + 1,
+            length: parse(1)?,
+            offset: parse(2)?,
+            line_bases: parse(3)?,
+            line_width: parse(4)?,
+        };
+        if record.line_bases == 0 || record.line_width < record.line_bases {
+            return Err(error::Error::FaiBadRecord(index + 1).into());
+        }
+
+        records.insert(fields[0].as_bytes().to_vec(), record);
+    }
+
+    Ok(records)
+}
+
+/// Read gzi index, pairs of compressed and uncompressed offset of each bgzip block
+fn read_gzi(mut input: impl std::io::Read) -> error::Result<Vec<(u64, u64)>> {
+    let mut buffer = [0; 8];
+    input.read_exact(&mut buffer)?;
+    let count = u64::from_le_bytes(buffer);
+
+    // first block isn't store in gzi
+    let mut blocks = vec![(0, 0)];
+    for _ in 0..count {
+        input.read_exact(&mut buffer)?;
+        let compressed = u64::from_le_bytes(buffer);
+        input.read_exact(&mut buffer)?;
+        let uncompressed = u64::from_le_bytes(buffer);
+
+        blocks.push((compressed, uncompressed));
+    }
+
+    Ok(blocks)
+}
+
+/// Compression of fasta file
+enum Compression {
+    Plain,
+    Bgzip(Vec<(u64, u64)>),
+}
+
+/// Fasta file, plain or bgzip compressed, with a pool of file handles
+struct Fasta {
+    path: std::path::PathBuf,
+    compression: Compression,
+    handles: std::sync::Mutex<Vec<std::fs::File>>,
+}
+
+impl Fasta {
+    /// Read length bytes of uncompressed fasta starting at offset
+    ///
+    /// Each read use its own file handle, take from pool or open, lock is only held to get and give
+    /// back handle.
+    fn read(&self, offset: u64, length: usize) -> error::Result<Vec<u8>> {
+        let handle = lock(&self.handles).pop();
+        let mut file = match handle {
+            Some(file) => file,
+            None => std::fs::File::open(&self.path)?,
+        };
+        let mut buffer = vec![0; length];
+
+        match &self.compression {
+            Compression::Plain => {
+                file.seek(std::io::SeekFrom::Start(offset))?;
+                file.read_exact(&mut buffer)?;
+            }
+            Compression::Bgzip(blocks) => {
+                let index = blocks.partition_point(|(_, uncompressed)| *uncompressed <= offset) - 1;
+                let (compressed, uncompressed) = blocks[index];
+
+                file.seek(std::io::SeekFrom::Start(compressed))?;
+                let mut decoder =
+                    flate2::read::MultiGzDecoder::new(std::io::BufReader::new(&mut file));
+                std::io::copy(
+                    &mut (&mut decoder).take(offset - uncompressed),
+                    &mut std::io::sink(),
+                )?;
+                decoder.read_exact(&mut buffer)?;
+            }
+        }
+
+        lock(&self.handles).push(file);
+
+        Ok(buffer)
+    }
+}
+
+/// Reference sequence read on demand with fai index, and gzi index if fasta is bgzip compressed.
+///
+/// Sequence is read by block of 65,536 bases, the least recently used blocks are drop when more
+/// than `cache_blocks` blocks are in memory. Lock of blocks cache is only held to get or insert a
+/// block, reading and decompression of missing block is done without lock.
+pub struct Indexed {
+    records: ahash::AHashMap<Vec<u8>, FaiRecord>,
+    fasta: Fasta,
+    blocks: std::sync::Mutex<lru::LruCache<(Vec<u8>, u64), std::sync::Arc<Vec<u8>>>>,
+    block_size: u64,
+}
+
+impl Indexed {
+    /// Open fasta at path, index is read from path with .fai suffix
+    pub fn from_path(path: &std::path::Path, cache_blocks: usize) -> error::Result<Self> {
+        Self::with_block_size(path, cache_blocks, BLOCK_SIZE)
+    }
+
+    fn with_block_size(
+        path: &std::path::Path,
+        cache_blocks: usize,
+        block_size: u64,
+    ) -> error::Result<Self> {
+        let records = read_fai(std::io::BufReader::new(std::fs::File::open(with_suffix(
+            path, ".fai",
+        ))?))?;
+
+        let mut file = std::fs::File::open(path)?;
+        let mut magic = [0; 2];
+        let compressed = file.read(&mut magic)? == 2 && magic == [0x1f, 0x8b];
+
+        let compression = if compressed {
+            let gzi_path = with_suffix(path, ".gzi");
+            if !gzi_path.exists() {
+                return Err(error::Error::GziMissing(gzi_path.display().to_string()).into());
+            }
+
+            Compression::Bgzip(read_gzi(std::io::BufReader::new(std::fs::File::open(
+                gzi_path,
+            )?))?)
+        } else {
+            Compression::Plain
+        };
+
+        Ok(Self {
+            records,
+            fasta: Fasta {
+                path: path.to_path_buf(),
+                compression,
+                handles: std::sync::Mutex::new(vec![file]),
+            },
+            blocks: std::sync::Mutex::new(lru::LruCache::new(
+                std::num::NonZeroUsize::new(cache_blocks).unwrap_or(std::num::NonZeroUsize::MIN),
+            )),
+            block_size,
+        })
+    }
+
+    /// Get block, read it from fasta if it isn't in cache
+    fn block(
+        &self,
+        seqname: &[u8],
+        record: &FaiRecord,
+        index: u64,
+    ) -> error::Result<std::sync::Arc<Vec<u8>>> {
+        let key = (seqname.to_vec(), index);
+
+        if let Some(block) = lock(&self.blocks).get(&key) {
+            return Ok(block.clone());
+        }
+
+        // block is read without lock, two threads could read same block, last one is keep
+        let start = index * self.block_size;
+        let end = std::cmp::min(start + self.block_size, record.length);
+        let first_byte = record.byte_of(start);
+        let last_byte = record.byte_of(end - 1);
+
+        let mut sequence = self
+            .fasta
+            .read(first_byte, (last_byte - first_byte + 1) as usize)?;
+        sequence.retain(|c| *c != b'\n' && *c != b'\r');
+        if sequence.len() as u64 != end - start {
+            return Err(error::Error::FaiBadRecord(record.line).into());
+        }
+
+        let block = std::sync::Arc::new(sequence);
+        lock(&self.blocks).put(key, block.clone());
+
+        Ok(block)
+    }
+}
+
+impl sequences_db::SequenceSource for Indexed {
+    fn fetch(
+        &self,
+        seqname: &[u8],
+        interval: &core::ops::Range<u64>,
+    ) -> error::Result<std::borrow::Cow<'_, [u8]>> {
+        let record = self
+            .records
+            .get(seqname)
+            .ok_or(error::Error::SeqNotInReferences(unsafe {
+                String::from_utf8_unchecked(seqname.to_vec())
+            }))?;
+
+        if interval.start > interval.end || interval.end > record.length {
+            return Err(error::Error::IntervalNotInSeq {
+                interval: interval.clone(),
+                name: unsafe { String::from_utf8_unchecked(seqname.to_vec()) },
+            }
+            .into());
+        }
+
+        let mut result = Vec::with_capacity((interval.end - interval.start) as usize);
+        if interval.start == interval.end {
+            return Ok(std::borrow::Cow::Owned(result));
+        }
+
+        for index in (interval.start / self.block_size)..=((interval.end - 1) / self.block_size) {
+            let block_start = index * self.block_size;
+            let block = self.block(seqname, record, index)?;
+
+            let start = interval.start.saturating_sub(block_start) as usize;
+            let end = std::cmp::min(interval.end - block_start, block.len() as u64) as usize;
+            result.extend_from_slice(&block[start..end]);
+        }
+
+        Ok(std::borrow::Cow::Owned(result))
+    }
+}
+
+/// Lock mutex, a panic in other thread didn't corrupt blocks cache or file handles pool
+fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Append suffix to path
+fn with_suffix(path: &std::path::Path, suffix: &str) -> std::path::PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(suffix);
+
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+    use std::io::Write as _;
+
+    /* project use */
+    use super::*;
+    use crate::sequences_db::SequenceSource as _;
+    use crate::test_data;
+
+    /// Write test reference with 60 bases by line and build its fai index
+    fn fasta_and_fai() -> (Vec<u8>, Vec<u8>) {
+        let reference = sequences_db::InMemory::from_reader(std::io::BufReader::new(Box::new(
+            test_data::SEQUENCE,
+        )
+            as Box<dyn std::io::Read + Send>))
+        .unwrap();
+
+        let mut names = reference.0.keys().cloned().collect::<Vec<Vec<u8>>>();
+        names.sort();
+
+        let mut fasta = Vec::new();
+        let mut fai = Vec::new();
+        for name in names {
+            let sequence = &reference.0[&name];
+            fasta.push(b'>');
+            fasta.extend(&name);
+            fasta.push(b'\n');
+
+            writeln!(
+                fai,
+                "{}\t{}\t{}\t60\t61",
+                String::from_utf8_lossy(&name),
+                sequence.len(),
+                fasta.len()
+            )
+            .unwrap();
+
+            for line in sequence.chunks(60) {
+                fasta.extend(line);
+                fasta.push(b'\n');
+            }
+        }
+
+        (fasta, fai)
+    }
+
+    fn check(indexed: &Indexed) -> error::Result<()> {
+        for interval in [
+            0..0,
+            0..10,
+            55..65,
+            241..281,
+            950..2100,
+            31000..31540,
+            0..31540,
+        ] {
+            assert_eq!(
+                test_data::SEQUENCE_DB.get_interval(b"chrA", &interval)?,
+                indexed.fetch(b"chrA", &interval)?,
+            );
+        }
+
+        assert!(indexed.fetch(b"chrA", &(31000..40000)).is_err());
+        assert!(indexed.fetch(b"chrZ", &(0..10)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plain() -> error::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("reference.fasta");
+
+        let (fasta, fai) = fasta_and_fai();
+        std::fs::write(&path, fasta)?;
+        std::fs::write(with_suffix(&path, ".fai"), fai)?;
+
+        check(&Indexed::from_path(&path, 16)?)?;
+        let indexed = Indexed::with_block_size(&path, 2, 1000)?;
+        check(&indexed)?;
+        assert_eq!(indexed.blocks.lock().unwrap().len(), 2);
+
+        // concurrent fetch share blocks cache
+        let indexed = Indexed::with_block_size(&path, 4, 1000)?;
+        std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(|| check(&indexed)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().unwrap())
+        })?;
+        assert_eq!(indexed.blocks.lock().unwrap().len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bgzip() -> error::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("reference.fasta.gz");
+
+        let (fasta, fai) = fasta_and_fai();
+
+        // each chunk is a gzip member, like bgzip block
+        let mut compressed = Vec::new();
+        let mut gzi = Vec::new();
+        for (index, chunk) in fasta.chunks(4096).enumerate() {
+            if index != 0 {
+                gzi.push((compressed.len() as u64, (index * 4096) as u64));
+            }
+            let mut encoder =
+                flate2::write::GzEncoder::new(&mut compressed, flate2::Compression::default());
+            encoder.write_all(chunk)?;
+            encoder.finish()?;
+        }
+        std::fs::write(&path, compressed)?;
+        std::fs::write(with_suffix(&path, ".fai"), fai)?;
+
+        assert!(Indexed::from_path(&path, 16).is_err());
+
+        let mut gzi_file = std::fs::File::create(with_suffix(&path, ".gzi"))?;
+        gzi_file.write_all(&(gzi.len() as u64).to_le_bytes())?;
+        for (compressed, uncompressed) in gzi {
+            gzi_file.write_all(&compressed.to_le_bytes())?;
+            gzi_file.write_all(&uncompressed.to_le_bytes())?;
+        }
+        drop(gzi_file);
+
+        check(&Indexed::from_path(&path, 16)?)?;
+        check(&Indexed::with_block_size(&path, 2, 1000)?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn bad_fai() {
+        assert!(read_fai(&b"chrA\t100\t6\t60\t61\n"[..]).is_ok());
+        assert!(read_fai(&b"chrA\t100\t6\t60\n"[..]).is_err());
+        assert!(read_fai(&b"chrA\t100\t6\t0\t1\n"[..]).is_err());
+        assert!(read_fai(&b"chrA\tabc\t6\t60\t61\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Reference sequence read in a memory mapped cache
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+use crate::sequences_db;
+
+/// Sequences are slices of a memory mapped file, bases are read by system on demand
+pub struct Mapped {
+    data: memmap2::Mmap,
+    sequences: ahash::AHashMap<Vec<u8>, core::ops::Range<usize>>,
+}
+
+impl Mapped {
+    /// Create a Mapped from file mapping and position of each sequence in mapping
+    pub fn new(
+        data: memmap2::Mmap,
+        sequences: ahash::AHashMap<Vec<u8>, core::ops::Range<usize>>,
+    ) -> Self {
+        Self { data, sequences }
+    }
+}
+
+impl sequences_db::SequenceSource for Mapped {
+    fn fetch(
+        &self,
+        seqname: &[u8],
+        interval: &core::ops::Range<u64>,
+    ) -> error::Result<std::borrow::Cow<'_, [u8]>> {
+        let range = self
+            .sequences
+            .get(seqname)
+            .ok_or(error::Error::SeqNotInReferences(
+                String::from_utf8_lossy(seqname).to_string(),
+            ))?;
+
+        Ok(std::borrow::Cow::Borrowed(
+            self.data[range.clone()]
+                .get((interval.start as usize)..(interval.end as usize))
+                .ok_or(error::Error::IntervalNotInSeq {
+                    interval: interval.clone(),
+                    name: String::from_utf8_lossy(seqname).to_string(),
+                })?,
+        ))
+    }
+}
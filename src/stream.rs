@@ -0,0 +1,155 @@
+//! Read annotations and reference one sequence after the other
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+
+/// Get records of one sequence after the other, records of a sequence must be contiguous
+///
+/// If sequences are request in file order, file is read only one time. A sequence before current
+/// position cause a new read from start of file, a sequence absent of file cost at most one read
+/// to end of file.
+pub struct BySeqname<O, I>
+where
+    I: Iterator,
+{
+    open: O,
+    records: std::iter::Peekable<I>,
+    passed: ahash::AHashSet<Vec<u8>>,
+    known: Option<ahash::AHashSet<Vec<u8>>>,
+}
+
+impl<O, I, T> BySeqname<O, I>
+where
+    O: FnMut() -> error::Result<I>,
+    I: Iterator<Item = error::Result<(Vec<u8>, T)>>,
+{
+    /// Create a BySeqname, `open` is call to read file from start
+    pub fn new(mut open: O) -> error::Result<Self> {
+        let records = open()?.peekable();
+
+        Ok(Self {
+            open,
+            records,
+            passed: ahash::AHashSet::new(),
+            known: None,
+        })
+    }
+
+    /// Get records of seqname, empty if file didn't contains seqname
+    pub fn get(&mut self, seqname: &[u8]) -> error::Result<Vec<T>> {
+        if self
+            .known
+            .as_ref()
+            .is_some_and(|known| !known.contains(seqname))
+        {
+            return Ok(vec![]);
+        }
+
+        if self.passed.contains(seqname) {
+            log::warn!(
+                "Sequences order didn't match, file is read again to get {}",
+                String::from_utf8_lossy(seqname)
+            );
+            self.records = (self.open)()?.peekable();
+            self.passed.clear();
+        }
+
+        loop {
+            let current = match self.records.peek() {
+                None => {
+                    // all sequences of file was passed
+                    self.known = Some(self.passed.clone());
+                    return Ok(vec![]);
+                }
+                Some(Ok((current, _))) => current.clone(),
+                Some(Err(_)) => return self.records.next().transpose().map(|_| vec![]),
+            };
+
+            if !self.passed.insert(current.clone()) {
+                return Err(error::Error::UnsortedSeqname(
+                    String::from_utf8_lossy(&current).to_string(),
+                )
+                .into());
+            }
+
+            let block = std::iter::from_fn(|| {
+                self.records
+                    .next_if(|result| result.as_ref().map_or(true, |(other, _)| other == &current))
+            });
+
+            if current == seqname {
+                return block
+                    .map(|result| result.map(|(_, record)| record))
+                    .collect();
+            }
+
+            for result in block {
+                result?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use super::*;
+
+    #[test]
+    fn by_seqname() -> error::Result<()> {
+        let opened = std::cell::Cell::new(0);
+        let records: Vec<(&[u8], u64)> =
+            vec![(&b"chrA"[..], 1), (b"chrA", 2), (b"chrB", 3), (b"chrC", 4)];
+        let open =
+            || -> error::Result<_> {
+                opened.set(opened.get() + 1);
+                Ok(records.clone().into_iter().map(
+                    |(name, value)| -> error::Result<(Vec<u8>, u64)> { Ok((name.to_vec(), value)) },
+                ))
+            };
+
+        let mut reader = BySeqname::new(open)?;
+
+        // File order
+        assert_eq!(reader.get(b"chrA")?, vec![1, 2]);
+        assert_eq!(reader.get(b"chrC")?, vec![4]);
+        assert_eq!(opened.get(), 1);
+
+        // Sequence before current position
+        assert_eq!(reader.get(b"chrB")?, vec![3]);
+        assert_eq!(opened.get(), 2);
+
+        // Absent sequence read to end of file one time
+        assert_eq!(reader.get(b"chrD")?, Vec::<u64>::new());
+        assert_eq!(reader.get(b"chrE")?, Vec::<u64>::new());
+        assert_eq!(opened.get(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsorted() -> error::Result<()> {
+        let records: Vec<(&[u8], u64)> = vec![(&b"chrA"[..], 1), (b"chrB", 2), (b"chrA", 3)];
+        let open =
+            || -> error::Result<_> {
+                Ok(records.clone().into_iter().map(
+                    |(name, value)| -> error::Result<(Vec<u8>, u64)> { Ok((name.to_vec(), value)) },
+                ))
+            };
+
+        let mut reader = BySeqname::new(open)?;
+
+        assert_eq!(reader.get(b"chrB")?, vec![2]);
+        assert!(reader.get(b"chrC").is_err());
+
+        Ok(())
+    }
+}
@@ -9,12 +9,16 @@ use bstr::ByteSlice;
 
 /* project use */
 use crate::annotation;
+use crate::annotations_db;
 use crate::sequences_db;
 use crate::variant;
 
 /// GFF file
 pub const GFF: &[u8] = std::include_bytes!("test_data/annotations.gff3");
 
+/// GTF file, same annotations as GFF file
+pub const GTF: &[u8] = std::include_bytes!("test_data/annotations.gtf");
+
 /// Sequence file
 pub const SEQUENCE: &[u8] = std::include_bytes!("test_data/references.fasta");
 
@@ -53,7 +57,14 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"ENSG00000286586.2".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"".to_vec(),
+                    parents: vec![],
+                    attributes: vec![
+                        (b"gene_id".to_vec(), b"ENSG00000286586.2".to_vec()),
+                        (b"gene_type".to_vec(), b"lncRNA".to_vec()),
+                        (b"gene_name".to_vec(), b"ENSG00000286586".to_vec()),
+                        (b"level".to_vec(), b"2".to_vec()),
+                        (b"havana_gene".to_vec(), b"OTTHUMG00000194565.1".to_vec()),
+                    ],
                 },
             },
             annotation::Annotation {
@@ -68,7 +79,18 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"ENST00000797271.1".to_vec(),
                     name: b"transcript_name".to_vec(),
-                    parent: b"ENSG00000286586.2".to_vec(),
+                    parents: vec![b"ENSG00000286586.2".to_vec()],
+                    attributes: vec![
+                        (b"gene_id".to_vec(), b"ENSG00000286586.2".to_vec()),
+                        (b"transcript_id".to_vec(), b"ENST00000797271.1".to_vec()),
+                        (b"gene_type".to_vec(), b"lncRNA".to_vec()),
+                        (b"gene_name".to_vec(), b"ENSG00000286586".to_vec()),
+                        (b"transcript_type".to_vec(), b"lncRNA".to_vec()),
+                        (b"transcript_name".to_vec(), b"ENST00000797271".to_vec()),
+                        (b"level".to_vec(), b"2".to_vec()),
+                        (b"tag".to_vec(), b"basic,Ensembl_canonical,TAGENE".to_vec()),
+                        (b"havana_gene".to_vec(), b"OTTHUMG00000194565.1".to_vec()),
+                    ],
                 },
             },
             annotation::Annotation {
@@ -83,7 +105,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"UTR5:ENST00000797271.1".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000797271.1".to_vec(),
+                    parents: vec![b"ENST00000797271.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -98,7 +121,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000797271.1:1".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000797271.1".to_vec(),
+                    parents: vec![b"ENST00000797271.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -113,7 +137,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000797271.1:2".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000797271.1".to_vec(),
+                    parents: vec![b"ENST00000797271.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -128,7 +153,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000797271.1:3".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000797271.1".to_vec(),
+                    parents: vec![b"ENST00000797271.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -143,7 +169,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000797271.1:4".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000797271.1".to_vec(),
+                    parents: vec![b"ENST00000797271.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -158,7 +185,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000797271.1:5".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000797271.1".to_vec(),
+                    parents: vec![b"ENST00000797271.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -173,7 +201,13 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"ENSG00000309035.1".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"".to_vec(),
+                    parents: vec![],
+                    attributes: vec![
+                        (b"gene_id".to_vec(), b"ENSG00000309035.1".to_vec()),
+                        (b"gene_type".to_vec(), b"lncRNA".to_vec()),
+                        (b"gene_name".to_vec(), b"ENSG00000309035".to_vec()),
+                        (b"level".to_vec(), b"2".to_vec()),
+                    ],
                 },
             },
             annotation::Annotation {
@@ -188,7 +222,17 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"ENST00000837983.1".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENSG00000309035.1".to_vec(),
+                    parents: vec![b"ENSG00000309035.1".to_vec()],
+                    attributes: vec![
+                        (b"gene_id".to_vec(), b"ENSG00000309035.1".to_vec()),
+                        (b"transcript_id".to_vec(), b"ENST00000837983.1".to_vec()),
+                        (b"gene_type".to_vec(), b"lncRNA".to_vec()),
+                        (b"gene_name".to_vec(), b"ENSG00000309035".to_vec()),
+                        (b"transcript_type".to_vec(), b"lncRNA".to_vec()),
+                        (b"transcript_name".to_vec(), b"ENST00000837983".to_vec()),
+                        (b"level".to_vec(), b"2".to_vec()),
+                        (b"tag".to_vec(), b"basic,Ensembl_canonical,TAGENE".to_vec()),
+                    ],
                 },
             },
             annotation::Annotation {
@@ -203,7 +247,8 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000837983.1:1".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000837983.1".to_vec(),
+                    parents: vec![b"ENST00000837983.1".to_vec()],
+                    attributes: vec![],
                 },
             },
             annotation::Annotation {
@@ -218,12 +263,51 @@ pub static GFF_ANNOTATION: std::sync::LazyLock<Vec<annotation::Annotation>> =
                 attribute: annotation::Attribute {
                     id: b"exon:ENST00000837983.1:2".to_vec(),
                     name: b"".to_vec(),
-                    parent: b"ENST00000837983.1".to_vec(),
+                    parents: vec![b"ENST00000837983.1".to_vec()],
+                    attributes: vec![],
                 },
             },
         ]
     });
 
+/// Small GFF of genes and transcripts use to test annotators
+///
+/// - chrA: coding tr1 and non coding tr2 (gene g2 is missing), both in g1 region, tr3 in g3
+/// - chrB: tr4 forward and tr5 reverse with three exons in g4, reverse tr6 in g5
+pub const TRANSCRIPTS_GFF: &[u8] = b"chrA\ttest\tgene\t51\t1000\t.\t+\t.\tID=g1;Name=gene1
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr1;Parent=g1
+chrA\ttest\texon\t51\t100\t.\t+\t.\tID=exon1;Parent=tr1
+chrA\ttest\texon\t201\t300\t.\t+\t.\tID=exon2;Parent=tr1
+chrA\ttest\tstart_codon\t61\t63\t.\t+\t0\tID=start1;Parent=tr1
+chrA\ttest\tstop_codon\t221\t223\t.\t+\t0\tID=stop1;Parent=tr1
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr2;Parent=g2
+chrA\ttest\texon\t51\t100\t.\t+\t.\tID=exon3;Parent=tr2
+chrA\ttest\texon\t201\t300\t.\t+\t.\tID=exon4;Parent=tr2
+chrA\ttest\tgene\t1001\t2000\t.\t+\t.\tID=g3
+chrA\ttest\ttranscript\t1001\t2000\t.\t+\t.\tID=tr3;Parent=g3
+chrB\ttest\tgene\t51\t300\t.\t+\t.\tID=g4
+chrB\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr4;Parent=g4
+chrB\ttest\texon\t51\t100\t.\t+\t.\tID=exon5;Parent=tr4
+chrB\ttest\texon\t151\t200\t.\t+\t.\tID=exon6;Parent=tr4
+chrB\ttest\texon\t251\t300\t.\t+\t.\tID=exon7;Parent=tr4
+chrB\ttest\ttranscript\t51\t300\t.\t-\t.\tID=tr5;Parent=g4
+chrB\ttest\texon\t51\t100\t.\t-\t.\tID=exon8;Parent=tr5
+chrB\ttest\texon\t151\t200\t.\t-\t.\tID=exon9;Parent=tr5
+chrB\ttest\texon\t251\t300\t.\t-\t.\tID=exon10;Parent=tr5
+chrB\ttest\tgene\t1001\t2000\t.\t-\t.\tID=g5
+chrB\ttest\ttranscript\t1001\t2000\t.\t-\t.\tID=tr6;Parent=g5
+";
+
+/// Annotations database of TRANSCRIPTS_GFF
+pub static TRANSCRIPTS_DB: std::sync::LazyLock<annotations_db::AnnotationsDataBase> =
+    std::sync::LazyLock::new(|| {
+        annotations_db::AnnotationsDataBase::from_reader(
+            std::io::BufReader::new(Box::new(TRANSCRIPTS_GFF) as Box<dyn std::io::Read + Send>),
+            100,
+        )
+        .unwrap()
+    });
+
 /// Sequence database
 pub static SEQUENCE_DB: std::sync::LazyLock<sequences_db::SequencesDataBase> =
     std::sync::LazyLock::new(|| {
@@ -243,6 +327,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"T".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"T".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -250,6 +345,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"C".to_vec(),
                 alt_seq: b"CT".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"CT".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -257,6 +363,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"G".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"G".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -264,6 +381,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"C".to_vec(),
                 alt_seq: b"G".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"G".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -271,6 +399,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"G".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"G".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -278,6 +417,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"C".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"C".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -285,6 +435,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"C".to_vec(),
                 alt_seq: b"G".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"G".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -292,6 +453,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"T".to_vec(),
                 alt_seq: b"TCTC".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"TCTC".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -299,6 +471,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"C".to_vec(),
                 alt_seq: b"T".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"T".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -306,6 +489,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"G".to_vec(),
                 alt_seq: b"A".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"A".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -313,6 +507,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"T".to_vec(),
                 alt_seq: b"g".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"g".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -320,6 +525,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"c".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"c".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -327,6 +543,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"G".to_vec(),
                 alt_seq: b"Gtgcg".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"Gtgcg".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -334,6 +561,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"T".to_vec(),
                 alt_seq: b"Tgg".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"Tgg".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -341,6 +579,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"ACTT".to_vec(),
                 alt_seq: b"A".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"A".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -348,6 +597,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"AA".to_vec(),
                 alt_seq: b"A".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"A".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -355,6 +615,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"TC".to_vec(),
                 alt_seq: b"T".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"T".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -362,6 +633,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"GCTCT".to_vec(),
                 alt_seq: b"G".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"G".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -369,6 +651,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"T".to_vec(),
                 alt_seq: b"Tggat".to_vec(),
                 variant_type: variant::Type::Small,
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b".".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"Tggat".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -376,6 +669,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"<CNV>".to_vec(),
                 variant_type: variant::Type::Cnv(589),
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b"SVLEN=589".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"<CNV>".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -383,6 +687,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"C".to_vec(),
                 alt_seq: b"<INS>".to_vec(),
                 variant_type: variant::Type::Ins(613),
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b"SVLEN=613".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"<INS>".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -390,6 +705,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"C".to_vec(),
                 alt_seq: b"<DEL>".to_vec(),
                 variant_type: variant::Type::Del(974),
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b"SVLEN=974".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"<DEL>".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -397,6 +723,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"<DUP>".to_vec(),
                 variant_type: variant::Type::Dup(753),
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b"SVLEN=753".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"<DUP>".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
             variant::Variant {
                 seqname: b"chrA".to_vec(),
@@ -404,6 +741,17 @@ pub static VARIANT_RECORD: std::sync::LazyLock<Vec<variant::Variant>> =
                 ref_seq: b"A".to_vec(),
                 alt_seq: b"<CNV>".to_vec(),
                 variant_type: variant::Type::Cnv(907),
+                id: b".".to_vec(),
+                quality: b"99".to_vec(),
+                filter: b"PASS".to_vec(),
+                info: b"SVLEN=907".to_vec(),
+                format: vec![],
+                samples: vec![],
+                allele_index: 0,
+                allele_count: 1,
+                alts: b"<CNV>".to_vec(),
+                original: None,
+                confidence: (0, 0),
             },
         ]
     });
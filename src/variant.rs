@@ -1,12 +1,75 @@
 //! Variant database
 
 /* std use */
+use std::io::BufRead as _;
 
 /* crate use */
 use bstr::ByteSlice as _;
 
+/* module declaration */
+mod bcf;
+mod region;
+
 /* project use */
 use crate::error;
+use crate::sequences_db;
+
+/* reexport */
+pub use bcf::BcfReader;
+pub use region::Region;
+pub use region::RegionReader;
+
+#[derive(Clone, PartialEq, Debug)]
+/// Store mate position and orientation of a breakend
+pub struct Breakend {
+    /// Sequence name of mate
+    pub seqname: Vec<u8>,
+    /// Position of mate (0-based)
+    pub position: u64,
+    /// Sequence before variant position is keep (t[p[ and t]p]), otherwise sequence after is keep
+    pub keep_before: bool,
+    /// Sequence after mate position is join (t[p[ and [p[t), otherwise sequence before is join
+    pub mate_after: bool,
+}
+
+impl Breakend {
+    /// Parse breakend notation of ALT column (t[p[, t]p], ]p]t or [p[t), None if alt isn't a mated breakend
+    pub fn from_alt(alt: &[u8]) -> error::Result<Option<Self>> {
+        let bracket = match alt.iter().find(|c| **c == b'[' || **c == b']') {
+            Some(bracket) => *bracket,
+            None => return Ok(None),
+        };
+
+        let mut fields = alt.split(|c| *c == bracket);
+        let (before, mate) = match (fields.next(), fields.next(), fields.next(), fields.next()) {
+            (Some(before), Some(mate), Some(after), None)
+                if before.is_empty() != after.is_empty() =>
+            {
+                (before, mate)
+            }
+            _ => return Err(error::Error::VcfBadBreakend.into()),
+        };
+
+        let (seqname, position) = mate
+            .rsplit_once_str(b":")
+            .ok_or(error::Error::VcfBadBreakend)?;
+        let position = unsafe { String::from_utf8_unchecked(position.to_vec()).parse::<u64>()? }
+            .checked_sub(1)
+            .ok_or(error::Error::VcfBadBreakend)?;
+
+        Ok(Some(Self {
+            seqname: seqname.to_vec(),
+            position,
+            keep_before: !before.is_empty(),
+            mate_after: bracket == b'[',
+        }))
+    }
+
+    /// Both side of junction keep their orientation, false if mate sequence is reverse complemented
+    pub fn same_orientation(&self) -> bool {
+        self.keep_before == self.mate_after
+    }
+}
 
 #[derive(Clone, PartialEq, Debug)]
 /// Store type of variant
@@ -23,59 +86,84 @@ pub enum Type {
     Inv(u64),
     /// Variant are a copy number variation (alt_seq contains <CNV>)
     Cnv(u64),
+    /// Variant are a breakend (alt_seq contains breakend notation)
+    Bnd(Breakend),
 }
 
 impl Type {
     /// Create a Type from alt_seq
-    pub fn from_alt(alt: &[u8], optional_infos: Option<&[u8]>) -> error::Result<Self> {
+    pub fn from_alt(
+        alt: &[u8],
+        optional_infos: Option<&[u8]>,
+        position: u64,
+    ) -> error::Result<Self> {
+        Type::from_allele(alt, optional_infos, 0, position)
+    }
+
+    /// Create a Type from alt_seq of allele at index allele_index, SVLEN value associate to this allele is used
+    ///
+    /// If SVLEN is absent length of structural variant is compute from END and position (0-based)
+    pub fn from_allele(
+        alt: &[u8],
+        optional_infos: Option<&[u8]>,
+        allele_index: usize,
+        position: u64,
+    ) -> error::Result<Self> {
+        if let Some(breakend) = Breakend::from_alt(alt)? {
+            return Ok(Type::Bnd(breakend));
+        }
+
         let mut opt_svlen = None;
+        let mut opt_end = None;
         if let Some(infos) = optional_infos {
             for info in infos.split_str(";") {
-                if let [b'S', b'V', b'L', b'E', b'N', b'=', value @ ..] = info {
-                    opt_svlen =
-                        unsafe { Some(String::from_utf8_unchecked(value.to_vec()).parse::<u64>()?) }
+                match info {
+                    [b'S', b'V', b'L', b'E', b'N', b'=', values @ ..] => {
+                        if let Some(value) = values
+                            .split_str(",")
+                            .nth(allele_index)
+                            .filter(|v| *v != b".")
+                        {
+                            // Some caller use negative SVLEN for deletion
+                            opt_svlen = unsafe {
+                                Some(
+                                    String::from_utf8_unchecked(value.to_vec())
+                                        .parse::<i64>()?
+                                        .unsigned_abs(),
+                                )
+                            }
+                        }
+                    }
+                    [b'E', b'N', b'D', b'=', value @ ..] => {
+                        opt_end = unsafe {
+                            Some(String::from_utf8_unchecked(value.to_vec()).parse::<u64>()?)
+                        }
+                    }
+                    _ => (),
                 }
             }
         }
 
-        match alt {
-            b"<INS>" => {
-                if let Some(svlen) = opt_svlen {
-                    Ok(Type::Ins(svlen))
-                } else {
-                    Err(error::Error::VcfStructVariantNoSvLen.into())
-                }
-            }
-            b"<DEL>" => {
-                if let Some(svlen) = opt_svlen {
-                    Ok(Type::Del(svlen))
-                } else {
-                    Err(error::Error::VcfStructVariantNoSvLen.into())
-                }
-            }
-            b"<DUP>" => {
-                if let Some(svlen) = opt_svlen {
-                    Ok(Type::Dup(svlen))
-                } else {
-                    Err(error::Error::VcfStructVariantNoSvLen.into())
-                }
-            }
-            b"<INV>" => {
-                if let Some(svlen) = opt_svlen {
-                    Ok(Type::Inv(svlen))
-                } else {
-                    Err(error::Error::VcfStructVariantNoSvLen.into())
-                }
-            }
-            b"<CNV>" => {
-                if let Some(svlen) = opt_svlen {
-                    Ok(Type::Cnv(svlen))
-                } else {
-                    Err(error::Error::VcfStructVariantNoSvLen.into())
-                }
-            }
-            _ => Ok(Type::Small),
-        }
+        // END is 1-based position of last base, first base is padding base
+        let opt_length = opt_svlen.or(opt_end.map(|end| end.saturating_sub(position + 1)));
+
+        // Symbolic allele could have subtypes (<DEL:ME:ALU>, <DUP:TANDEM>), only first level is used
+        let symbolic = alt
+            .strip_prefix(b"<")
+            .and_then(|a| a.strip_suffix(b">"))
+            .and_then(|a| a.split(|c| *c == b':').next());
+        let constructor = match symbolic {
+            Some(b"INS") => Type::Ins,
+            Some(b"DEL") => Type::Del,
+            Some(b"DUP") => Type::Dup,
+            Some(b"INV") => Type::Inv,
+            Some(b"CNV") => Type::Cnv,
+            _ => return Ok(Type::Small),
+        };
+
+        opt_length
+            .map(constructor)
+            .ok_or(error::Error::VcfStructVariantNoSvLen.into())
     }
 }
 
@@ -88,8 +176,47 @@ impl std::fmt::Display for Type {
             Type::Dup(length) => write!(f, "<DUP:{}>", length),
             Type::Inv(length) => write!(f, "<INV:{}>", length),
             Type::Cnv(length) => write!(f, "<CNV:{}>", length),
+            Type::Bnd(breakend) => write!(
+                f,
+                "<BND:{}:{}>",
+                String::from_utf8_lossy(&breakend.seqname),
+                breakend.position
+            ),
+        }
+    }
+}
+
+/// Get how many bases structural variant could start before and end after its interval from CIPOS and CIEND
+fn confidence_interval(infos: &[u8]) -> error::Result<(u64, u64)> {
+    let mut confidence = (0, 0);
+
+    for info in infos.split_str(";") {
+        match info {
+            [b'C', b'I', b'P', b'O', b'S', b'=', values @ ..] => {
+                let value = values
+                    .split_str(",")
+                    .next()
+                    .ok_or(error::Error::VcfBadRecord)?;
+                confidence.0 = unsafe {
+                    (-String::from_utf8_unchecked(value.to_vec()).parse::<i64>()?).max(0) as u64
+                };
+            }
+            [b'C', b'I', b'E', b'N', b'D', b'=', values @ ..] => {
+                let value = values
+                    .split_str(",")
+                    .nth(1)
+                    .ok_or(error::Error::VcfBadRecord)?;
+                confidence.1 = unsafe {
+                    String::from_utf8_unchecked(value.to_vec())
+                        .parse::<i64>()?
+                        .max(0) as u64
+                };
+            }
+            _ => (),
         }
     }
+
+    Ok(confidence)
 }
 
 #[derive(Clone, PartialEq)]
@@ -114,40 +241,160 @@ pub struct Variant {
     /// Store the type of variant
     #[cfg_attr(feature = "json", serde(skip_serializing))]
     pub variant_type: Type,
+
+    /// Identifiant of variant (ID column)
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::serialize_bstr"))]
+    pub id: Vec<u8>,
+
+    /// Quality of variant (QUAL column)
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::serialize_bstr"))]
+    pub quality: Vec<u8>,
+
+    /// Filter status of variant (FILTER column)
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::serialize_bstr"))]
+    pub filter: Vec<u8>,
+
+    /// Additional information of variant (INFO column)
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::serialize_bstr"))]
+    pub info: Vec<u8>,
+
+    /// Genotype format (FORMAT column), empty if vcf didn't contains sample
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    pub format: Vec<u8>,
+
+    /// Genotype value of each sample
+    #[cfg_attr(
+        feature = "json",
+        serde(
+            serialize_with = "crate::serialize_vec_bstr",
+            skip_serializing_if = "Vec::is_empty"
+        )
+    )]
+    pub samples: Vec<Vec<u8>>,
+
+    /// Index of alt_seq in ALT column (0-based)
+    pub allele_index: usize,
+
+    /// Number of allele in ALT column
+    #[cfg_attr(feature = "json", serde(skip_serializing))]
+    pub allele_count: usize,
+
+    /// ALT column as write in vcf, with all alleles
+    #[cfg_attr(feature = "json", serde(skip_serializing))]
+    pub alts: Vec<u8>,
+
+    /// Position, reference and alternative sequence as write in vcf, None if variant wasn't normalized
+    #[cfg_attr(feature = "json", serde(skip_serializing))]
+    pub original: Option<(u64, Vec<u8>, Vec<u8>)>,
+
+    /// Number of bases structural variant could start before position and end after interval (CIPOS and CIEND)
+    #[cfg_attr(feature = "json", serde(skip_serializing))]
+    pub confidence: (u64, u64),
 }
 
 impl Variant {
-    /// Build a Variant from csv::ByteRecord
-    pub fn from_byte_record(record: csv::ByteRecord) -> error::Result<Self> {
+    /// Build Variants from csv::ByteRecord, one Variant is create for each allele of ALT column
+    pub fn from_byte_record(record: csv::ByteRecord) -> error::Result<Vec<Self>> {
         let seqname = record.get(0).ok_or(error::Error::VcfBadRecord)?.to_vec();
         let position = unsafe {
             String::from_utf8_unchecked(record.get(1).ok_or(error::Error::VcfBadRecord)?.to_vec())
                 .parse::<u64>()?
-        } - 1;
+        }
+        .checked_sub(1)
+        .ok_or(error::Error::VcfBadRecord)?;
+        let id = record.get(2).ok_or(error::Error::VcfBadRecord)?.to_vec();
         let ref_seq = record.get(3).ok_or(error::Error::VcfBadRecord)?.to_vec();
-        let alt_seq = record.get(4).ok_or(error::Error::VcfBadRecord)?.to_vec();
-        let info = record.get(7);
+        let alts = record.get(4).ok_or(error::Error::VcfBadRecord)?;
+        let quality = record.get(5).unwrap_or(b".").to_vec();
+        let filter = record.get(6).unwrap_or(b".").to_vec();
+        let info = record.get(7).unwrap_or(b".").to_vec();
+        let format = record.get(8).unwrap_or(b"").to_vec();
+        let samples = record
+            .iter()
+            .skip(9)
+            .map(|c| c.to_vec())
+            .collect::<Vec<Vec<u8>>>();
 
-        let variant_type = Type::from_alt(&alt_seq, info)?;
+        let allele_count = alts.split_str(",").count();
+        let confidence = confidence_interval(&info)?;
 
-        Ok(Self {
-            seqname,
-            position,
-            ref_seq,
-            alt_seq,
-            variant_type,
-        })
+        alts.split_str(",")
+            .enumerate()
+            .map(|(allele_index, alt_seq)| {
+                Ok(Self {
+                    seqname: seqname.clone(),
+                    position,
+                    ref_seq: ref_seq.clone(),
+                    alt_seq: alt_seq.to_vec(),
+                    variant_type: Type::from_allele(alt_seq, Some(&info), allele_index, position)?,
+                    id: id.clone(),
+                    quality: quality.clone(),
+                    filter: filter.clone(),
+                    info: info.clone(),
+                    format: format.clone(),
+                    samples: samples.clone(),
+                    allele_index,
+                    allele_count,
+                    alts: alts.to_vec(),
+                    original: None,
+                    confidence,
+                })
+            })
+            .collect()
+    }
+
+    /// Build a Variant from raw text of a record that can't be parse, missing or invalid fields are empty
+    pub fn from_invalid_record(record: &[u8]) -> Self {
+        let fields = record.split_str("\t").collect::<Vec<&[u8]>>();
+        let field = |index: usize| fields.get(index).copied().unwrap_or(b"").to_vec();
+
+        Self {
+            seqname: field(0),
+            position: String::from_utf8_lossy(&field(1))
+                .parse::<u64>()
+                .unwrap_or(0)
+                .saturating_sub(1),
+            ref_seq: field(3),
+            alt_seq: field(4),
+            variant_type: Type::Small,
+            id: field(2),
+            quality: field(5),
+            filter: field(6),
+            info: field(7),
+            format: field(8),
+            samples: fields.iter().skip(9).map(|c| c.to_vec()).collect(),
+            allele_index: 0,
+            allele_count: 1,
+            alts: field(4),
+            original: None,
+            confidence: (0, 0),
+        }
     }
 
     /// Create interval associate with variant
+    ///
+    /// Interval of structural variant is extend by CIPOS and CIEND
     pub fn get_interval(&self) -> core::ops::Range<u64> {
-        match self.variant_type {
+        let interval = match self.variant_type {
             Type::Small => self.position..self.position + self.ref_seq.len() as u64,
             Type::Ins(_size) => self.position..self.position + 1,
             Type::Del(size) => self.position..self.position + size,
             Type::Dup(size) => self.position..self.position + size * 2,
             Type::Inv(size) => self.position..self.position + size,
             Type::Cnv(size) => self.position..self.position + size,
+            Type::Bnd(_) => self.position..self.position + 1,
+        };
+
+        if self.structural() {
+            interval.start.saturating_sub(self.confidence.0)..interval.end + self.confidence.1
+        } else {
+            interval
         }
     }
 
@@ -181,6 +428,150 @@ impl Variant {
         self.variant_type != Type::Small
     }
 
+    /// Reference sequence of variant didn't match genome reference
+    ///
+    /// Variant on sequence absent of genome reference or invalid variant are never mismatch
+    pub fn ref_mismatch(&self, sequences: &sequences_db::SequencesDataBase) -> bool {
+        if !self.valid() {
+            return false;
+        }
+
+        let (position, ref_seq, _) = self.vcf_allele();
+        match sequences.get_interval(&self.seqname, &(position..position + ref_seq.len() as u64)) {
+            Ok(reference) => !reference.eq_ignore_ascii_case(ref_seq),
+            Err(e) => matches!(
+                e.downcast_ref::<error::Error>(),
+                Some(error::Error::IntervalNotInSeq { .. })
+            ),
+        }
+    }
+
+    /// Position, reference and alternative sequence as write in vcf
+    pub fn vcf_allele(&self) -> (u64, &[u8], &[u8]) {
+        match &self.original {
+            Some((position, ref_seq, alt_seq)) => (*position, ref_seq, alt_seq),
+            None => (self.position, &self.ref_seq, &self.alt_seq),
+        }
+    }
+
+    /// Trim common bases and shift indel to the left most position (vcf normalization)
+    pub fn left_align(&mut self, sequences: &sequences_db::SequencesDataBase) {
+        self.align(sequences, false)
+    }
+
+    /// Trim common bases and shift indel to the right most position (HGVS 3' rule on forward strand)
+    pub fn right_align(&mut self, sequences: &sequences_db::SequencesDataBase) {
+        self.align(sequences, true)
+    }
+
+    fn align(&mut self, sequences: &sequences_db::SequencesDataBase, right: bool) {
+        if self.structural() || !self.valid() {
+            return;
+        }
+
+        // Variant can't be normalize against a reference it didn't match
+        match sequences.get_interval(&self.seqname, &self.get_interval()) {
+            Ok(reference) if reference.eq_ignore_ascii_case(&self.ref_seq) => (),
+            _ => return,
+        }
+
+        let prefix = self
+            .ref_seq
+            .iter()
+            .zip(self.alt_seq.iter())
+            .take_while(|(r, a)| r.eq_ignore_ascii_case(a))
+            .count();
+        let suffix = self.ref_seq[prefix..]
+            .iter()
+            .rev()
+            .zip(self.alt_seq[prefix..].iter().rev())
+            .take_while(|(r, a)| r.eq_ignore_ascii_case(a))
+            .count();
+
+        let mut start = self.position + prefix as u64;
+        let ref_seq = &self.ref_seq[prefix..self.ref_seq.len() - suffix];
+        let alt_seq = &self.alt_seq[prefix..self.alt_seq.len() - suffix];
+
+        let base = |position: u64| {
+            sequences
+                .get_interval(&self.seqname, &(position..position + 1))
+                .ok()
+                .and_then(|s| s.first().copied())
+        };
+
+        let (position, new_ref, new_alt) = match (ref_seq.is_empty(), alt_seq.is_empty()) {
+            (true, true) => return,
+            (false, false) => (start, ref_seq.to_vec(), alt_seq.to_vec()),
+            (insertion, _) => {
+                let deletion = !insertion;
+                let mut indel = if deletion {
+                    ref_seq.to_vec()
+                } else {
+                    alt_seq.to_vec()
+                };
+                let ref_len = if deletion { indel.len() as u64 } else { 0 };
+
+                if right {
+                    while let Some(next) = base(start + ref_len) {
+                        if !next.eq_ignore_ascii_case(&indel[0]) {
+                            break;
+                        }
+                        indel.rotate_left(1);
+                        if deletion {
+                            *indel.last_mut().unwrap() = next; // indel isn't empty
+                        }
+                        start += 1;
+                    }
+                } else {
+                    while let Some(previous) = start.checked_sub(1).and_then(base) {
+                        if !previous.eq_ignore_ascii_case(&indel[indel.len() - 1]) {
+                            break;
+                        }
+                        indel.rotate_right(1);
+                        if deletion {
+                            indel[0] = previous;
+                        }
+                        start -= 1;
+                    }
+                }
+
+                let (mut new_ref, mut new_alt) = if deletion {
+                    (indel, vec![])
+                } else {
+                    (vec![], indel)
+                };
+
+                if let Some(anchor) = start.checked_sub(1).and_then(base) {
+                    new_ref.insert(0, anchor);
+                    new_alt.insert(0, anchor);
+                    (start - 1, new_ref, new_alt)
+                } else if let Some(anchor) = base(start + ref_len) {
+                    new_ref.push(anchor);
+                    new_alt.push(anchor);
+                    (start, new_ref, new_alt)
+                } else {
+                    return;
+                }
+            }
+        };
+
+        if position == self.position && new_ref == self.ref_seq && new_alt == self.alt_seq {
+            return;
+        }
+
+        if self.original.is_none() {
+            self.original = Some((
+                self.position,
+                std::mem::take(&mut self.ref_seq),
+                std::mem::take(&mut self.alt_seq),
+            ));
+        }
+
+        self.position = position;
+        self.ref_seq = new_ref;
+        self.alt_seq = new_alt;
+    }
+
     #[cfg(test)]
     /// Generate a fake variant with a seqname, position, ref_seq, alt_seqdb
     pub fn test_variant(
@@ -195,7 +586,18 @@ impl Variant {
             position,
             ref_seq: ref_seq.to_vec(),
             alt_seq: alt_seq.to_vec(),
-            variant_type: Type::from_alt(alt_seq, opt_info)?,
+            variant_type: Type::from_alt(alt_seq, opt_info, position)?,
+            id: b".".to_vec(),
+            quality: b".".to_vec(),
+            filter: b".".to_vec(),
+            info: opt_info.unwrap_or(b".").to_vec(),
+            format: vec![],
+            samples: vec![],
+            allele_index: 0,
+            allele_count: 1,
+            alts: alt_seq.to_vec(),
+            original: None,
+            confidence: confidence_interval(opt_info.unwrap_or(b"."))?,
         })
     }
 }
@@ -227,12 +629,27 @@ impl std::fmt::Display for Variant {
     }
 }
 
+/// Decode bcf input in vcf text, other input is return unchanged
+pub fn decode(
+    input: Box<dyn std::io::Read + std::marker::Send>,
+) -> error::Result<Box<dyn std::io::Read + std::marker::Send>> {
+    let mut input = std::io::BufReader::new(input);
+
+    if input.fill_buf()?.starts_with(b"BCF") {
+        Ok(Box::new(BcfReader::new(input)?))
+    } else {
+        Ok(Box::new(input))
+    }
+}
+
 /// Struct to generate Variant iterator from vcf
 pub struct VcfReader<R>
 where
     R: std::io::BufRead,
 {
     inner: csv::Reader<R>,
+    header: Vec<Vec<u8>>,
+    alleles: std::collections::VecDeque<Variant>,
 }
 
 impl<R> VcfReader<R>
@@ -240,15 +657,38 @@ where
     R: std::io::BufRead,
 {
     /// Create a VcfReader from a std::io::BufRead
-    pub fn from_reader(inner: R) -> Self {
+    pub fn from_reader(mut inner: R) -> Self {
+        let mut header = Vec::new();
+
+        let mut line = Vec::new();
+        while let Ok([b'#', ..]) = inner.fill_buf() {
+            line.clear();
+            if inner.read_until(b'\n', &mut line).is_err() {
+                break;
+            }
+
+            while let Some(b'\n' | b'\r') = line.last() {
+                line.pop();
+            }
+            header.push(line.clone());
+        }
+
         Self {
             inner: csv::ReaderBuilder::new()
                 .delimiter(b'\t')
                 .has_headers(false)
                 .comment(Some(b'#'))
+                .flexible(true)
                 .from_reader(inner),
+            header,
+            alleles: std::collections::VecDeque::new(),
         }
     }
+
+    /// Get header lines of vcf
+    pub fn header(&self) -> &[Vec<u8>] {
+        &self.header
+    }
 }
 
 impl<R> std::iter::Iterator for VcfReader<R>
@@ -258,9 +698,29 @@ where
     type Item = error::Result<Variant>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(variant) = self.alleles.pop_front() {
+            return Some(Ok(variant));
+        }
+
         let mut record = csv::ByteRecord::new();
         match self.inner.read_byte_record(&mut record) {
-            Ok(true) => Some(Variant::from_byte_record(record)),
+            Ok(true) => {
+                let line = record.position().map_or(0, |p| p.line()) + self.header.len() as u64;
+                let raw = record.iter().collect::<Vec<&[u8]>>().join(&b'\t');
+
+                match Variant::from_byte_record(record) {
+                    Ok(variants) => {
+                        self.alleles.extend(variants);
+                        self.alleles.pop_front().map(Ok)
+                    }
+                    Err(e) => Some(Err(error::Error::VcfInvalidRecord {
+                        line,
+                        record: String::from_utf8_lossy(&raw).to_string(),
+                        message: e.to_string(),
+                    }
+                    .into())),
+                }
+            }
             Ok(false) => None,
             Err(e) => Some(Err(e.into())),
         }
@@ -285,6 +745,80 @@ mod tests {
         assert_eq!(format!("{}", Type::Dup(10)), "<DUP:10>");
         assert_eq!(format!("{}", Type::Inv(10)), "<INV:10>");
         assert_eq!(format!("{}", Type::Cnv(10)), "<CNV:10>");
+        assert_eq!(
+            format!("{}", Type::from_alt(b"G[chrB:101[", None, 0)?),
+            "<BND:chrB:100>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn structural_type() -> error::Result<()> {
+        // SVLEN or END
+        assert_eq!(
+            Type::from_alt(b"<DEL>", Some(b"SVLEN=-100"), 9)?,
+            Type::Del(100)
+        );
+        assert_eq!(
+            Type::from_alt(b"<DEL>", Some(b"END=110"), 9)?,
+            Type::Del(100)
+        );
+        assert_eq!(
+            Type::from_alt(b"<INV>", Some(b"SVLEN=50;END=110"), 9)?,
+            Type::Inv(50)
+        );
+        assert!(Type::from_alt(b"<DUP>", Some(b"DP=10"), 9).is_err());
+
+        // Subtypes
+        assert_eq!(
+            Type::from_alt(b"<DEL:ME:ALU>", Some(b"SVLEN=-300"), 9)?,
+            Type::Del(300)
+        );
+        assert_eq!(
+            Type::from_alt(b"<DUP:TANDEM>", Some(b"END=110"), 9)?,
+            Type::Dup(100)
+        );
+        assert_eq!(
+            Type::from_alt(b"<INS:ME>", Some(b"SVLEN=300"), 9)?,
+            Type::Ins(300)
+        );
+        assert_eq!(Type::from_alt(b"<DELETION>", None, 9)?, Type::Small);
+
+        // Breakend
+        assert_eq!(
+            Type::from_alt(b"G]chr:B:11]", None, 9)?,
+            Type::Bnd(Breakend {
+                seqname: b"chr:B".to_vec(),
+                position: 10,
+                keep_before: true,
+                mate_after: false,
+            })
+        );
+        assert_eq!(
+            Type::from_alt(b"[chrB:11[G", None, 9)?,
+            Type::Bnd(Breakend {
+                seqname: b"chrB".to_vec(),
+                position: 10,
+                keep_before: false,
+                mate_after: true,
+            })
+        );
+        assert!(Type::from_alt(b"G[chrB:11[G", None, 9).is_err());
+        assert!(Type::from_alt(b"G[chrB[", None, 9).is_err());
+
+        // Confidence interval
+        let variant = Variant::test_variant(
+            b"chrA",
+            100,
+            b"A",
+            b"<DEL>",
+            Some(b"SVLEN=50;CIPOS=-10,5;CIEND=-3,20"),
+        )?;
+        assert_eq!(variant.get_interval(), 90..170);
+        let variant =
+            Variant::test_variant(b"chrA", 100, b"A", b"A[chrB:11[", Some(b"CIPOS=-5,5"))?;
+        assert_eq!(variant.get_interval(), 95..101);
 
         Ok(())
     }
@@ -308,6 +842,203 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn keep_record() -> error::Result<()> {
+        let mut reader = VcfReader::from_reader(std::io::Cursor::new(
+            b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\ts1\ts2
+chr1\t10\trs42\tA\tT\t30\tq10\tDP=4\tGT:DP\t0/1:2\t1/1:2
+",
+        ));
+
+        assert_eq!(
+            reader.header(),
+            &[
+                b"##fileformat=VCFv4.3".to_vec(),
+                b"#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\ts1\ts2".to_vec()
+            ]
+        );
+
+        let variant = reader.next().unwrap()?;
+        assert_eq!(variant.position, 9);
+        assert_eq!(variant.id, b"rs42");
+        assert_eq!(variant.quality, b"30");
+        assert_eq!(variant.filter, b"q10");
+        assert_eq!(variant.info, b"DP=4");
+        assert_eq!(variant.format, b"GT:DP");
+        assert_eq!(variant.samples, vec![b"0/1:2".to_vec(), b"1/1:2".to_vec()]);
+
+        assert!(reader.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn multi_allelic() -> error::Result<()> {
+        let mut reader = VcfReader::from_reader(std::io::Cursor::new(
+            b"chr1\t10\t.\tA\tT,<DEL>,AC\t30\t.\tSVLEN=.,5,.
+chr1\t20\t.\tC\tG\t30\t.\t.
+",
+        ));
+
+        let variants = reader
+            .by_ref()
+            .take(3)
+            .collect::<error::Result<Vec<Variant>>>()?;
+
+        assert_eq!(
+            variants
+                .iter()
+                .map(|v| (v.alt_seq.clone(), v.allele_index, v.allele_count))
+                .collect::<Vec<(Vec<u8>, usize, usize)>>(),
+            vec![
+                (b"T".to_vec(), 0, 3),
+                (b"<DEL>".to_vec(), 1, 3),
+                (b"AC".to_vec(), 2, 3)
+            ]
+        );
+        assert_eq!(variants[1].variant_type, Type::Del(5));
+        assert!(variants.iter().all(|v| v.valid() || v.structural()));
+
+        let variant = reader.next().unwrap()?;
+        assert_eq!(variant.alt_seq, b"G");
+        assert_eq!((variant.allele_index, variant.allele_count), (0, 1));
+
+        assert!(reader.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_record() -> error::Result<()> {
+        let mut reader = VcfReader::from_reader(std::io::Cursor::new(
+            b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT
+chr1\t10\t.\tA\tT
+chr1\tten\t.\tA\tT
+chr1\t0\t.\tA\tT
+chr1\t30
+chr1\t40\t.\tC\tG
+",
+        ));
+
+        assert_eq!(reader.next().unwrap()?.position, 9);
+        for (line, record) in [
+            (4, "chr1\tten\t.\tA\tT"),
+            (5, "chr1\t0\t.\tA\tT"),
+            (6, "chr1\t30"),
+        ] {
+            let error = reader.next().unwrap().unwrap_err();
+            match error.downcast_ref::<error::Error>() {
+                Some(error::Error::VcfInvalidRecord {
+                    line: l, record: r, ..
+                }) => assert_eq!((*l, r.as_str()), (line, record)),
+                _ => panic!("unexpected error {}", error),
+            }
+        }
+        assert_eq!(reader.next().unwrap()?.position, 39);
+        assert!(reader.next().is_none());
+
+        let variant = Variant::from_invalid_record(b"chr1\tten\trs1\tA\tT,G");
+        assert_eq!(variant.seqname, b"chr1");
+        assert_eq!(variant.position, 0);
+        assert_eq!(variant.id, b"rs1");
+        assert_eq!(variant.ref_seq, b"A");
+        assert_eq!(variant.alt_seq, b"T,G");
+        assert_eq!(variant.quality, b"");
+
+        Ok(())
+    }
+
+    #[test]
+    fn align() -> error::Result<()> {
+        // reference at 240: GTTCAATTTCAATA
+        let mut variant = Variant::test_variant(b"chrA", 247, b"TT", b"T", None)?;
+        variant.left_align(&test_data::SEQUENCE_DB);
+        assert_eq!(
+            (
+                variant.position,
+                variant.ref_seq.as_slice(),
+                variant.alt_seq.as_slice()
+            ),
+            (245, &b"AT"[..], &b"A"[..])
+        );
+        assert_eq!(variant.vcf_allele(), (247, &b"TT"[..], &b"T"[..]));
+
+        let mut variant = Variant::test_variant(b"chrA", 247, b"TT", b"T", None)?;
+        variant.right_align(&test_data::SEQUENCE_DB);
+        assert_eq!(variant.original, None);
+
+        let mut variant = Variant::test_variant(b"chrA", 245, b"A", b"AA", None)?;
+        variant.left_align(&test_data::SEQUENCE_DB);
+        assert_eq!(
+            (
+                variant.position,
+                variant.ref_seq.as_slice(),
+                variant.alt_seq.as_slice()
+            ),
+            (243, &b"C"[..], &b"CA"[..])
+        );
+
+        let mut variant = Variant::test_variant(b"chrA", 240, b"GTT", b"GT", None)?;
+        variant.left_align(&test_data::SEQUENCE_DB);
+        assert_eq!(
+            (
+                variant.position,
+                variant.ref_seq.as_slice(),
+                variant.alt_seq.as_slice()
+            ),
+            (240, &b"GT"[..], &b"G"[..])
+        );
+
+        let mut variant = Variant::test_variant(b"chrA", 240, b"GTT", b"GT", None)?;
+        variant.right_align(&test_data::SEQUENCE_DB);
+        assert_eq!(
+            (
+                variant.position,
+                variant.ref_seq.as_slice(),
+                variant.alt_seq.as_slice()
+            ),
+            (241, &b"TT"[..], &b"T"[..])
+        );
+
+        let mut variant = Variant::test_variant(b"chrA", 240, b"GTTC", b"GAAC", None)?;
+        variant.left_align(&test_data::SEQUENCE_DB);
+        assert_eq!(
+            (
+                variant.position,
+                variant.ref_seq.as_slice(),
+                variant.alt_seq.as_slice()
+            ),
+            (241, &b"TT"[..], &b"AA"[..])
+        );
+
+        // reference mismatch variant isn't normalize
+        let mut variant = Variant::test_variant(b"chrA", 247, b"GT", b"G", None)?;
+        variant.left_align(&test_data::SEQUENCE_DB);
+        assert_eq!(variant.original, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ref_mismatch() -> error::Result<()> {
+        assert!(!Variant::test_variant(b"chrA", 240, b"GTT", b"G", None)?
+            .ref_mismatch(&test_data::SEQUENCE_DB));
+        assert!(!Variant::test_variant(b"chrA", 240, b"gtt", b"G", None)?
+            .ref_mismatch(&test_data::SEQUENCE_DB));
+        assert!(Variant::test_variant(b"chrA", 240, b"GAT", b"G", None)?
+            .ref_mismatch(&test_data::SEQUENCE_DB));
+        assert!(
+            Variant::test_variant(b"chrA", 100_000_000, b"A", b"G", None)?
+                .ref_mismatch(&test_data::SEQUENCE_DB)
+        );
+        assert!(!Variant::test_variant(b"chrZ", 240, b"A", b"G", None)?
+            .ref_mismatch(&test_data::SEQUENCE_DB));
+
+        Ok(())
+    }
+
     #[test]
     fn test_variant() -> error::Result<()> {
         let variant = Variant::test_variant(b"chr1", 62103, b"ACT", b"A", None)?; // 0-based
@@ -391,7 +1122,7 @@ mod tests {
             ".",
             "SVLEN=40",
         ]))?;
-        assert!(!bad_alt.valid());
+        assert!(!bad_alt[0].valid());
 
         Ok(())
     }
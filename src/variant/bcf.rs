@@ -0,0 +1,179 @@
+//! Decode bcf records in vcf text
+
+/* std use */
+
+/* crate use */
+use noodles::vcf::variant::io::Write as _;
+
+/* project use */
+use crate::error;
+
+/// Write vcf text of a bcf record, with end of line, at end of line buffer
+pub(crate) fn write_record(
+    header: &noodles::vcf::Header,
+    record: &noodles::bcf::Record,
+    line: &mut Vec<u8>,
+) -> error::Result<()> {
+    noodles::vcf::io::Writer::new(line).write_variant_record(header, record)?;
+
+    Ok(())
+}
+
+/// Write vcf text of bcf header
+pub(crate) fn header_text(header: &noodles::vcf::Header) -> error::Result<Vec<u8>> {
+    let mut text = Vec::new();
+    noodles::vcf::io::Writer::new(&mut text).write_header(header)?;
+
+    Ok(text)
+}
+
+/// Read an uncompressed bcf, header and records are convert in vcf text
+pub struct BcfReader<R> {
+    inner: noodles::bcf::io::Reader<R>,
+    header: noodles::vcf::Header,
+    record: noodles::bcf::Record,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R> BcfReader<R>
+where
+    R: std::io::Read,
+{
+    /// Create a BcfReader, header is read immediately
+    pub fn new(inner: R) -> error::Result<Self> {
+        let mut inner = noodles::bcf::io::Reader::from(inner);
+        let header = inner.read_header()?;
+        let buffer = header_text(&header)?;
+
+        Ok(Self {
+            inner,
+            header,
+            record: noodles::bcf::Record::default(),
+            buffer,
+            position: 0,
+        })
+    }
+}
+
+impl<R> std::io::Read for BcfReader<R>
+where
+    R: std::io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+
+            if self.inner.read_record(&mut self.record)? != 0 {
+                write_record(&self.header, &self.record, &mut self.buffer)
+                    .map_err(std::io::Error::other)?;
+            }
+        }
+
+        let length = (self.buffer.len() - self.position).min(buf.len());
+        buf[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
+        self.position += length;
+
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+    use std::io::Read as _;
+
+    /* crate use */
+    use bstr::ByteSlice as _;
+
+    /* project use */
+    use super::*;
+
+    const HEADER: &[u8] = b"##fileformat=VCFv4.3
+##FILTER=<ID=PASS,Description=\"All filters passed\">
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Depth, total\">
+##INFO=<ID=DB,Number=0,Type=Flag,Description=\"In database\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##contig=<ID=chrA,length=1000>
+##contig=<ID=chrB,length=1000>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample1\tsample2
+";
+
+    fn bcf(records: &[&[u8]]) -> Vec<u8> {
+        let mut data = b"BCF\x02\x02".to_vec();
+        data.extend((HEADER.len() as u32 + 1).to_le_bytes());
+        data.extend(HEADER);
+        data.push(0);
+        for record in records {
+            data.extend(*record);
+        }
+
+        data
+    }
+
+    fn record() -> Vec<u8> {
+        let mut shared = Vec::new();
+        shared.extend(1i32.to_le_bytes()); // chrB
+        shared.extend(9i32.to_le_bytes()); // position 10
+        shared.extend(1i32.to_le_bytes());
+        shared.extend(12.5f32.to_le_bytes());
+        shared.extend((2u32 | 3 << 16).to_le_bytes()); // 2 infos, 3 alleles
+        shared.extend((2u32 | 1 << 24).to_le_bytes()); // 2 samples, 1 format
+        shared.extend([0x37, b'r', b's', b'1']); // id
+        shared.extend([0x17, b'A', 0x17, b'C', 0x27, b'G', b'T']); // alleles
+        shared.extend([0x11, 0]); // PASS
+        shared.extend([0x11, 1, 0x12]); // DP as int16
+        shared.extend(300i16.to_le_bytes());
+        shared.extend([0x11, 2, 0x00]); // DB flag
+
+        let mut individual = Vec::new();
+        individual.extend([0x11, 3, 0x21]); // GT, 2 int8 by sample
+        individual.extend([0x02, 0x05, 0x06, 0x81]); // 0|1 and 2
+
+        let mut record = Vec::new();
+        record.extend((shared.len() as u32).to_le_bytes());
+        record.extend((individual.len() as u32).to_le_bytes());
+        record.extend(shared);
+        record.extend(individual);
+
+        record
+    }
+
+    #[test]
+    fn reader() -> error::Result<()> {
+        let record = record();
+        let mut reader = BcfReader::new(std::io::Cursor::new(bcf(&[&record, &record])))?;
+
+        let mut text = Vec::new();
+        reader.read_to_end(&mut text)?;
+
+        let line: &[u8] = b"chrB\t10\trs1\tA\tC,GT\t12.5\tPASS\tDP=300;DB\tGT\t0|1\t2\n";
+        let mut records = line.to_vec();
+        records.extend(line);
+
+        assert!(text.starts_with(b"##fileformat=VCFv4.3\n"));
+        assert!(text.contains_str(
+            "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample1\tsample2\n"
+        ));
+        assert!(text.ends_with(&records));
+
+        Ok(())
+    }
+
+    #[test]
+    fn not_bcf() {
+        assert!(BcfReader::new(std::io::Cursor::new(HEADER)).is_err());
+    }
+
+    #[test]
+    fn truncated() -> error::Result<()> {
+        let record = record();
+        let mut reader = BcfReader::new(std::io::Cursor::new(bcf(&[&record[..record.len() - 2]])))?;
+
+        let mut text = Vec::new();
+        assert!(reader.read_to_end(&mut text).is_err());
+
+        Ok(())
+    }
+}
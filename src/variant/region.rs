@@ -0,0 +1,563 @@
+//! Read records of an indexed variant file that overlap regions
+
+/* std use */
+use std::io::BufRead as _;
+
+/* crate use */
+use bstr::ByteSlice as _;
+
+/* project use */
+use crate::error;
+use crate::variant::bcf;
+
+/// A region of a sequence
+#[derive(std::clone::Clone, std::fmt::Debug, PartialEq)]
+pub struct Region {
+    /// Name of sequence
+    pub seqname: Vec<u8>,
+    /// Start of region (0-based)
+    pub start: u64,
+    /// End of region (0-based, exclusive)
+    pub end: u64,
+}
+
+impl Region {
+    /// Read regions from a bed file, track, browser and comment lines are ignored
+    pub fn from_bed<R>(input: R) -> error::Result<Vec<Self>>
+    where
+        R: std::io::BufRead,
+    {
+        let mut regions = Vec::new();
+
+        for line in input.lines() {
+            let line = line?;
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("track")
+                || line.starts_with("browser")
+            {
+                continue;
+            }
+
+            let bad_region = || error::Error::RegionBad(line.clone());
+            let mut fields = line.split_whitespace();
+            let (Some(seqname), Some(start), Some(end)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(bad_region().into());
+            };
+
+            let start = start.parse::<u64>().map_err(|_| bad_region())?;
+            let end = end.parse::<u64>().map_err(|_| bad_region())?;
+            if end < start {
+                return Err(bad_region().into());
+            }
+
+            regions.push(Self {
+                seqname: seqname.as_bytes().to_vec(),
+                start,
+                end,
+            });
+        }
+
+        Ok(regions)
+    }
+}
+
+impl std::str::FromStr for Region {
+    type Err = error::Error;
+
+    /// Parse chr, chr:pos, chr:start-end or chr:start-, positions are 1-based and inclusive
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let bad_region = || error::Error::RegionBad(value.to_string());
+
+        let Some((seqname, range)) = value.rsplit_once(':') else {
+            if value.is_empty() {
+                return Err(bad_region());
+            }
+
+            return Ok(Self {
+                seqname: value.as_bytes().to_vec(),
+                start: 0,
+                end: u64::MAX,
+            });
+        };
+
+        let range = range.replace(',', "");
+        let parse = |position: &str| position.parse::<u64>().map_err(|_| bad_region());
+        let (start, end) = match range.split_once('-') {
+            Some((start, "")) => (parse(start)?, u64::MAX),
+            Some((start, end)) => (parse(start)?, parse(end)?),
+            None => (parse(&range)?, parse(&range)?),
+        };
+
+        if seqname.is_empty() || start == 0 || end < start {
+            return Err(bad_region());
+        }
+
+        Ok(Self {
+            seqname: seqname.as_bytes().to_vec(),
+            start: start - 1,
+            end,
+        })
+    }
+}
+
+/// Indexed variant file, bgzip vcf read as text or bcf decode in vcf text
+enum Format {
+    Vcf(noodles::bgzf::io::Reader<std::fs::File>),
+    Bcf {
+        reader: noodles::bcf::io::Reader<noodles::bgzf::io::Reader<std::fs::File>>,
+        header: noodles::vcf::Header,
+        record: noodles::bcf::Record,
+    },
+}
+
+impl Format {
+    /// Get bgzip reader of file
+    fn bgzf(&mut self) -> &mut noodles::bgzf::io::Reader<std::fs::File> {
+        match self {
+            Format::Vcf(reader) => reader,
+            Format::Bcf { reader, .. } => reader.get_mut(),
+        }
+    }
+
+    /// Read next record in line as vcf text, return false at end of file
+    fn read_record(&mut self, line: &mut Vec<u8>) -> error::Result<bool> {
+        match self {
+            Format::Vcf(reader) => Ok(reader.read_until(b'\n', line)? != 0),
+            Format::Bcf {
+                reader,
+                header,
+                record,
+            } => {
+                if reader.read_record(record)? == 0 {
+                    return Ok(false);
+                }
+                bcf::write_record(header, record, line)?;
+
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// Regions of one sequence, intervals are sorted and merged, with chunks of file to read
+struct Query {
+    seqname: Vec<u8>,
+    intervals: Vec<(u64, u64)>,
+    chunks: std::collections::VecDeque<(u64, u64)>,
+}
+
+/// Read records of a bgzip vcf or a bcf that overlap regions, with tabix or csi index.
+///
+/// Header and records are produce as vcf text, records are in file order and each record is
+/// produce only one time.
+pub struct RegionReader {
+    inner: Format,
+    queries: std::collections::VecDeque<Query>,
+    chunk_end: Option<u64>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl RegionReader {
+    /// Open variant file at path, index is read from path with .tbi or .csi suffix
+    pub fn from_path(path: &std::path::Path, regions: &[Region]) -> error::Result<Self> {
+        let index = read_index(path)?;
+        let mut reader = noodles::bgzf::io::Reader::new(std::fs::File::open(path)?);
+
+        let (inner, buffer) = if reader.fill_buf()?.starts_with(b"BCF") {
+            let mut reader = noodles::bcf::io::Reader::from(reader);
+            let header = reader.read_header()?;
+            let text = bcf::header_text(&header)?;
+
+            (
+                Format::Bcf {
+                    reader,
+                    header,
+                    record: noodles::bcf::Record::default(),
+                },
+                text,
+            )
+        } else {
+            let mut text = Vec::new();
+            while let [b'#', ..] = reader.fill_buf()? {
+                reader.read_until(b'\n', &mut text)?;
+            }
+
+            (Format::Vcf(reader), text)
+        };
+
+        // csi index of bcf didn't contains sequence names
+        let names = match (index.header(), &inner) {
+            (Some(header), _) => header
+                .reference_sequence_names()
+                .iter()
+                .map(|name| AsRef::<[u8]>::as_ref(name).to_vec())
+                .collect(),
+            (None, Format::Bcf { header, .. }) => header
+                .contigs()
+                .keys()
+                .map(|name| name.as_bytes().to_vec())
+                .collect(),
+            (None, Format::Vcf(_)) => Vec::new(),
+        };
+
+        let mut intervals = std::collections::BTreeMap::<usize, Vec<(u64, u64)>>::new();
+        for region in regions {
+            match names.iter().position(|name| name == &region.seqname) {
+                Some(id) => intervals
+                    .entry(id)
+                    .or_default()
+                    .push((region.start, region.end)),
+                None => log::warn!(
+                    "Region on {} is skipped, sequence isn't in variant index",
+                    String::from_utf8_lossy(&region.seqname)
+                ),
+            }
+        }
+
+        let mut queries = std::collections::VecDeque::new();
+        for (id, mut intervals) in intervals {
+            intervals.sort_unstable();
+
+            let mut merged: Vec<(u64, u64)> = Vec::with_capacity(intervals.len());
+            for (start, end) in intervals {
+                match merged.last_mut() {
+                    Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                    _ => merged.push((start, end)),
+                }
+            }
+
+            queries.push_back(Query {
+                seqname: names[id].clone(),
+                chunks: chunks(index.as_ref(), id, &merged)?.into(),
+                intervals: merged,
+            });
+        }
+
+        Ok(Self {
+            inner,
+            queries,
+            chunk_end: None,
+            buffer,
+            position: 0,
+        })
+    }
+
+    /// Read next record that overlap a region in buffer, return false if no more record
+    fn next_record(&mut self) -> error::Result<bool> {
+        loop {
+            let Some(query) = self.queries.front_mut() else {
+                return Ok(false);
+            };
+
+            let chunk_end = match self.chunk_end {
+                Some(end) => end,
+                None => match query.chunks.pop_front() {
+                    Some((begin, end)) => {
+                        self.inner
+                            .bgzf()
+                            .seek(noodles::bgzf::VirtualPosition::from(begin))?;
+                        self.chunk_end = Some(end);
+                        end
+                    }
+                    None => {
+                        self.queries.pop_front();
+                        continue;
+                    }
+                },
+            };
+
+            if u64::from(self.inner.bgzf().virtual_position()) >= chunk_end {
+                self.chunk_end = None;
+                continue;
+            }
+
+            self.buffer.clear();
+            if !self.inner.read_record(&mut self.buffer)? {
+                self.chunk_end = None;
+                continue;
+            }
+            if self.buffer.last() != Some(&b'\n') {
+                self.buffer.push(b'\n');
+            }
+
+            // record that can't be parse are keep, error is report by vcf reader
+            let Some((seqname, start, end)) = record_interval(&self.buffer) else {
+                return Ok(true);
+            };
+            if seqname != query.seqname {
+                continue;
+            }
+
+            // records are sorted, all next records of this sequence are after regions
+            if query.intervals.last().is_some_and(|last| start >= last.1) {
+                query.chunks.clear();
+                self.chunk_end = None;
+                continue;
+            }
+
+            let index = query.intervals.partition_point(|(_, stop)| *stop <= start);
+            if query
+                .intervals
+                .get(index)
+                .is_some_and(|(begin, _)| *begin < end)
+            {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+impl std::io::Read for RegionReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+
+            if !self.next_record().map_err(std::io::Error::other)? {
+                return Ok(0);
+            }
+        }
+
+        let length = (self.buffer.len() - self.position).min(buf.len());
+        buf[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
+        self.position += length;
+
+        Ok(length)
+    }
+}
+
+/// Read tabix or csi index of variant file at path
+fn read_index(path: &std::path::Path) -> error::Result<Box<dyn noodles::csi::BinningIndex>> {
+    let with_suffix = |suffix: &str| {
+        let mut path = path.as_os_str().to_os_string();
+        path.push(suffix);
+        std::path::PathBuf::from(path)
+    };
+    let bad_index =
+        |index: &std::path::Path| error::Error::VariantIndexBad(index.display().to_string());
+
+    let tabix = with_suffix(".tbi");
+    if tabix.exists() {
+        return Ok(Box::new(
+            noodles::tabix::fs::read(&tabix).map_err(|_| bad_index(&tabix))?,
+        ));
+    }
+
+    let csi = with_suffix(".csi");
+    if csi.exists() {
+        return Ok(Box::new(
+            noodles::csi::fs::read(&csi).map_err(|_| bad_index(&csi))?,
+        ));
+    }
+
+    Err(error::Error::VariantIndexMissing(path.display().to_string()).into())
+}
+
+/// Chunks of file, as pair of virtual positions, that could contains records of intervals, chunks
+/// are sorted and merged
+fn chunks(
+    index: &dyn noodles::csi::BinningIndex,
+    reference: usize,
+    intervals: &[(u64, u64)],
+) -> error::Result<Vec<(u64, u64)>> {
+    // positions after maximal position of index are query without end
+    let max_position = 1u64 << (u64::from(index.min_shift()) + 3 * u64::from(index.depth()));
+
+    let mut chunks = Vec::new();
+    for (start, end) in intervals {
+        let start = noodles::core::Position::try_from(*start as usize + 1)?;
+        let interval = if *end >= max_position {
+            noodles::core::region::Interval::from(start..)
+        } else {
+            let end = noodles::core::Position::try_from(*end as usize)?.max(start);
+            noodles::core::region::Interval::from(start..=end)
+        };
+
+        chunks.extend(
+            index
+                .query(reference, interval)?
+                .into_iter()
+                .map(|chunk| (u64::from(chunk.start()), u64::from(chunk.end()))),
+        );
+    }
+    chunks.sort_unstable();
+
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(chunks.len());
+    for (begin, end) in chunks {
+        match merged.last_mut() {
+            Some(last) if begin <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((begin, end)),
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Sequence name and interval covered by a vcf line, end is position of END in INFO if present
+fn record_interval(line: &[u8]) -> Option<(&[u8], u64, u64)> {
+    let mut fields = line.trim_end().split_str("\t");
+
+    let seqname = fields.next()?;
+    let start = fields
+        .next()?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()?
+        .checked_sub(1)?;
+    let ref_seq = fields.nth(1)?;
+    let info = fields.nth(3).unwrap_or(b".");
+
+    let end = info
+        .split_str(";")
+        .find_map(|field| field.strip_prefix(b"END="))
+        .and_then(|end| end.to_str().ok()?.parse::<u64>().ok())
+        .unwrap_or(0)
+        .max(start + (ref_seq.len() as u64).max(1));
+
+    Some((seqname, start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+    use std::io::Read as _;
+    use std::io::Write as _;
+
+    /* crate use */
+
+    /* project use */
+    use super::*;
+
+    #[test]
+    fn parse() -> error::Result<()> {
+        let region = |seqname: &[u8], start, end| Region {
+            seqname: seqname.to_vec(),
+            start,
+            end,
+        };
+
+        assert_eq!("chrA".parse::<Region>()?, region(b"chrA", 0, u64::MAX));
+        assert_eq!("chrA:10".parse::<Region>()?, region(b"chrA", 9, 10));
+        assert_eq!("chrA:10-20".parse::<Region>()?, region(b"chrA", 9, 20));
+        assert_eq!(
+            "chrA:1,000-".parse::<Region>()?,
+            region(b"chrA", 999, u64::MAX)
+        );
+
+        assert!("".parse::<Region>().is_err());
+        assert!("chrA:0-10".parse::<Region>().is_err());
+        assert!("chrA:20-10".parse::<Region>().is_err());
+        assert!("chrA:ten".parse::<Region>().is_err());
+        assert!(":10-20".parse::<Region>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bed() -> error::Result<()> {
+        let regions =
+            Region::from_bed(&b"track name=panel\n# comment\nchrA\t9\t20\tgene\n\nchrB 0 5\n"[..])?;
+
+        assert_eq!(
+            regions,
+            vec![
+                Region {
+                    seqname: b"chrA".to_vec(),
+                    start: 9,
+                    end: 20
+                },
+                Region {
+                    seqname: b"chrB".to_vec(),
+                    start: 0,
+                    end: 5
+                },
+            ]
+        );
+
+        assert!(Region::from_bed(&b"chrA\t20\n"[..]).is_err());
+        assert!(Region::from_bed(&b"chrA\t20\t10\n"[..]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn interval() {
+        assert_eq!(
+            record_interval(b"chrA\t10\t.\tACGT\tA\t.\t.\t.\n"),
+            Some((&b"chrA"[..], 9, 13))
+        );
+        assert_eq!(
+            record_interval(b"chrA\t10\t.\tA\t<DEL>\t.\t.\tSVTYPE=DEL;END=100\n"),
+            Some((&b"chrA"[..], 9, 100))
+        );
+        assert_eq!(record_interval(b"chrA\tten\t.\tA\tT\n"), None);
+    }
+
+    #[test]
+    fn reader() -> error::Result<()> {
+        let header = b"##fileformat=VCFv4.3\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n";
+        let record = |i: u64| {
+            format!(
+                "chr{}\t{}\t.\tAC\tA\t.\t.\t.\n",
+                if i < 20 { "A" } else { "B" },
+                (i % 20) * 10_000 + 1
+            )
+        };
+
+        let directory = tempfile::tempdir()?;
+        let path = directory.path().join("variants.vcf.gz");
+
+        let mut writer = noodles::bgzf::io::Writer::new(std::fs::File::create(&path)?);
+        writer.write_all(header)?;
+        for i in 0..40 {
+            writer.write_all(record(i).as_bytes())?;
+        }
+        writer.finish()?;
+
+        // index is required
+        assert!(RegionReader::from_path(&path, &[]).is_err());
+
+        noodles::tabix::fs::write(
+            directory.path().join("variants.vcf.gz.tbi"),
+            &noodles::vcf::fs::index(&path)?,
+        )?;
+
+        let query = |regions: &[&str]| -> error::Result<Vec<u8>> {
+            let regions = regions
+                .iter()
+                .map(|region| region.parse::<Region>())
+                .collect::<Result<Vec<Region>, error::Error>>()?;
+
+            let mut output = Vec::new();
+            RegionReader::from_path(&path, &regions)?.read_to_end(&mut output)?;
+
+            Ok(output)
+        };
+        let truth = |records: &[u64]| {
+            let mut text = header.to_vec();
+            for i in records {
+                text.extend(record(*i).as_bytes());
+            }
+            text
+        };
+
+        assert_eq!(query(&["chrA:1-100"])?, truth(&[0]));
+        // deletion at 10001 cover 10002
+        assert_eq!(query(&["chrA:10002"])?, truth(&[1]));
+        assert_eq!(query(&["chrA:10003-19999"])?, truth(&[]));
+        // records are produce one time in file order
+        assert_eq!(
+            query(&["chrB:190001-", "chrA:20001-30001", "chrA:1-20001"])?,
+            truth(&[0, 1, 2, 3, 39])
+        );
+        assert_eq!(query(&["chrB"])?, truth(&(20..40).collect::<Vec<u64>>()));
+        assert_eq!(query(&["chrC"])?, truth(&[]));
+
+        Ok(())
+    }
+}
@@ -3,14 +3,25 @@
 /* std use */
 
 /* crate use */
+use bstr::ByteSlice as _;
 
 /* module declaration */
 mod feature_presence;
+mod fusion;
+mod gene;
+mod hgvs;
 mod sequence_analysis;
+mod splice;
+mod structural;
+mod transcript_structure;
+
+pub use splice::SpliceWindow;
 
 /* project use */
+use crate::annotation;
 use crate::annotations_db;
 use crate::effect;
+use crate::error;
 use crate::memoizor;
 use crate::myth;
 use crate::sequences_db;
@@ -42,12 +53,95 @@ impl From<AnnotatorsChoicesRaw> for usize {
 /// Choose how variant are annotate
 pub type AnnotatorsChoices = enumflags2::BitFlags<AnnotatorsChoicesRaw>;
 
+#[derive(
+    std::clone::Clone, std::marker::Copy, std::fmt::Debug, std::default::Default, PartialEq,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+/// Choose what to do with variant where reference sequence didn't match genome reference
+pub enum RefMismatch {
+    /// Log a warning and annotate variant
+    #[default]
+    Warn,
+    /// Annotate variant and set a warning in each annotation
+    Flag,
+    /// Log a warning and skip variant, nothing is write
+    Skip,
+}
+
+#[derive(
+    std::clone::Clone, std::marker::Copy, std::fmt::Debug, std::default::Default, PartialEq,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+/// Choose what to do with vcf record that can't be parse
+pub enum BadRecord {
+    /// Stop with an error
+    #[default]
+    Fail,
+    /// Log a warning and skip record
+    Skip,
+    /// Log a warning and write a myth with invalid_record effect
+    Placeholder,
+}
+
+/// Number of variants annotate and variants with a problem
+#[derive(
+    std::clone::Clone, std::marker::Copy, std::fmt::Debug, std::default::Default, PartialEq,
+)]
+pub struct Counts {
+    /// Number of variant annotate
+    pub variants: u64,
+    /// Number of variant where reference sequence didn't match genome reference
+    pub ref_mismatch: u64,
+    /// Number of vcf record that can't be parse
+    pub bad_record: u64,
+}
+
+impl std::ops::AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.variants += other.variants;
+        self.ref_mismatch += other.ref_mismatch;
+        self.bad_record += other.bad_record;
+    }
+}
+
+/// Choose which transcript annotations are keep in myth
+#[derive(std::clone::Clone, std::fmt::Debug, std::default::Default, PartialEq)]
+pub enum TranscriptSelection {
+    /// Keep all transcripts
+    #[default]
+    All,
+    /// Keep one transcript by gene, with the most severe impact
+    MostSevere,
+    /// Keep only transcripts tagged Ensembl_canonical or MANE_Select
+    Canonical,
+    /// Keep only transcripts present in list, version suffix of id could be omit
+    Allowlist(ahash::AHashSet<Vec<u8>>),
+}
+
+/// Transcript tags that mark a canonical transcript
+const CANONICAL_TAGS: [&[u8]; 2] = [b"Ensembl_canonical", b"MANE_Select"];
+
+/// Half-open intervals share at least one position
+pub(crate) fn overlap(a: &core::ops::Range<u64>, b: &core::ops::Range<u64>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
 trait Annotator {
     fn annotate(
         &self,
         variant: &variant::Variant,
         memoizor: &mut memoizor::Memoizor,
     ) -> Vec<effect::Effect>;
+
+    /// Update AnnotationMyth of transcript, by default only add effect
+    fn annotate_myth(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+        annotation_myth: &mut myth::AnnotationMythBuilder,
+    ) {
+        annotation_myth.extend_effect(&self.annotate(variant, memoizor));
+    }
 }
 
 macro_rules! myth_fast_end {
@@ -68,9 +162,18 @@ macro_rules! myth_fast_end {
 /// Struct that associate to a variant myth
 pub struct Variant2Myth<'a> {
     annotations: &'a annotations_db::AnnotationsDataBase,
+    translate: &'a translate::Translate,
     sequences: &'a sequences_db::SequencesDataBase,
     annotators: [Vec<Box<dyn Annotator + std::marker::Send + std::marker::Sync + 'a>>; 5],
     annotators_choices: AnnotatorsChoices,
+    normalize: bool,
+    variants_count: std::sync::atomic::AtomicU64,
+    ref_mismatch: RefMismatch,
+    ref_mismatch_count: std::sync::atomic::AtomicU64,
+    bad_record: BadRecord,
+    bad_record_count: std::sync::atomic::AtomicU64,
+    transcript_selection: TranscriptSelection,
+    attributes: Vec<Vec<u8>>,
 }
 
 impl<'a> Variant2Myth<'a> {
@@ -81,10 +184,38 @@ impl<'a> Variant2Myth<'a> {
         sequences: &'a sequences_db::SequencesDataBase,
         annotators_choices: AnnotatorsChoices,
     ) -> Self {
+        Self {
+            annotations,
+            translate,
+            sequences,
+            annotators: Self::annotators(
+                annotations,
+                translate,
+                sequences,
+                SpliceWindow::default(),
+            ),
+            annotators_choices,
+            normalize: true,
+            variants_count: std::sync::atomic::AtomicU64::new(0),
+            ref_mismatch: RefMismatch::default(),
+            ref_mismatch_count: std::sync::atomic::AtomicU64::new(0),
+            bad_record: BadRecord::default(),
+            bad_record_count: std::sync::atomic::AtomicU64::new(0),
+            transcript_selection: TranscriptSelection::default(),
+            attributes: vec![],
+        }
+    }
+
+    fn annotators(
+        annotations: &'a annotations_db::AnnotationsDataBase,
+        translate: &'a translate::Translate,
+        sequences: &'a sequences_db::SequencesDataBase,
+        splice_window: SpliceWindow,
+    ) -> [Vec<Box<dyn Annotator + std::marker::Send + std::marker::Sync + 'a>>; 5] {
         let mut annotators: [Vec<Box<dyn Annotator + std::marker::Send + std::marker::Sync>>; 5] =
             [Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()];
 
-        annotators[usize::from(AnnotatorsChoicesRaw::Gene)].extend([]);
+        annotators[usize::from(AnnotatorsChoicesRaw::Gene)].push(Box::new(gene::Gene::new()));
         annotators[usize::from(AnnotatorsChoicesRaw::Feature)].extend([
             Box::new(feature_presence::FeaturePresence::new(
                 b"upstream",
@@ -103,21 +234,273 @@ impl<'a> Variant2Myth<'a> {
                 effect::Effect::ThreePrimeUtrVariant,
             )) as Box<dyn Annotator + Send + Sync>,
         ]);
-        annotators[usize::from(AnnotatorsChoicesRaw::Effect)].push(Box::new(
-            sequence_analysis::SequenceAnalysis::new(translate, sequences),
-        ));
-        annotators[usize::from(AnnotatorsChoicesRaw::Hgvs)].extend([]);
+        annotators[usize::from(AnnotatorsChoicesRaw::Effect)].extend([
+            Box::new(sequence_analysis::SequenceAnalysis::new(translate))
+                as Box<dyn Annotator + Send + Sync>,
+            Box::new(splice::Splice::new(splice_window)) as Box<dyn Annotator + Send + Sync>,
+            Box::new(transcript_structure::TranscriptStructure::new())
+                as Box<dyn Annotator + Send + Sync>,
+            Box::new(structural::Structural::new()) as Box<dyn Annotator + Send + Sync>,
+            Box::new(fusion::Fusion::new(annotations)) as Box<dyn Annotator + Send + Sync>,
+        ]);
+        annotators[usize::from(AnnotatorsChoicesRaw::Hgvs)]
+            .push(Box::new(hgvs::Hgvs::new(translate, sequences)));
 
-        Self {
-            annotations,
-            sequences,
-            annotators,
-            annotators_choices,
+        annotators
+    }
+
+    /// Set size of splice site and splice region windows
+    pub fn splice_window(mut self, splice_window: SpliceWindow) -> Self {
+        self.annotators = Self::annotators(
+            self.annotations,
+            self.translate,
+            self.sequences,
+            splice_window,
+        );
+        self
+    }
+
+    /// Set if variant are trim and left align against reference before annotation, default true
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Set what to do with variant where reference sequence didn't match genome reference, default warn
+    pub fn ref_mismatch(mut self, ref_mismatch: RefMismatch) -> Self {
+        self.ref_mismatch = ref_mismatch;
+        self
+    }
+
+    /// Set what to do with vcf record that can't be parse, default fail
+    pub fn bad_record(mut self, bad_record: BadRecord) -> Self {
+        self.bad_record = bad_record;
+        self
+    }
+
+    /// Set which transcript annotations are keep, default all
+    pub fn transcript_selection(mut self, transcript_selection: TranscriptSelection) -> Self {
+        self.transcript_selection = transcript_selection;
+        self
+    }
+
+    /// Set attribute keys copy from transcript, or gene if missing, in annotations, default none
+    pub fn attributes(mut self, attributes: Vec<Vec<u8>>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Number of variant annotate by record
+    pub fn variants_count(&self) -> u64 {
+        self.variants_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of variant where reference sequence didn't match genome reference
+    pub fn ref_mismatch_count(&self) -> u64 {
+        self.ref_mismatch_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of vcf record that can't be parse
+    pub fn bad_record_count(&self) -> u64 {
+        self.bad_record_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of variants annotate and variants with a problem
+    pub fn counts(&self) -> Counts {
+        Counts {
+            variants: self.variants_count(),
+            ref_mismatch: self.ref_mismatch_count(),
+            bad_record: self.bad_record_count(),
+        }
+    }
+
+    /// Generate myth associate to a vcf reader result, None if record is skip
+    ///
+    /// Record that can't be parse is manage as set by bad_record, other error are return.
+    pub fn record(
+        &self,
+        result: error::Result<variant::Variant>,
+    ) -> error::Result<Option<myth::Myth>> {
+        let error = match result {
+            Ok(variant) => {
+                self.variants_count
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return Ok(self.myth(variant));
+            }
+            Err(error) => error,
+        };
+
+        let Some(error::Error::VcfInvalidRecord { record, .. }) =
+            error.downcast_ref::<error::Error>()
+        else {
+            return Err(error);
+        };
+
+        if self.bad_record == BadRecord::Fail {
+            return Err(error);
+        }
+
+        self.bad_record_count
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        log::warn!("{}", error);
+
+        match self.bad_record {
+            BadRecord::Placeholder => {
+                let mut myth = myth::Myth::from_variant(variant::Variant::from_invalid_record(
+                    record.as_bytes(),
+                ));
+                myth.add_annotation(
+                    myth::AnnotationMyth::from_nowhere()
+                        .effects(vec![effect::Effect::InvalidRecord])
+                        .build()
+                        .unwrap(), // No possible error in build
+                );
+
+                Ok(Some(myth))
+            }
+            _ => Ok(None),
         }
     }
 
-    /// Generate myth associate to variant
-    pub fn myth(&self, variant: variant::Variant) -> myth::Myth {
+    /// Generate myth associate to variant, None if variant is skip
+    pub fn myth(&self, variant: variant::Variant) -> Option<myth::Myth> {
+        let mut myth = self.check_reference(variant)?;
+
+        self.select(&mut myth);
+
+        Some(myth)
+    }
+
+    fn check_reference(&self, mut variant: variant::Variant) -> Option<myth::Myth> {
+        if !variant.ref_mismatch(self.sequences) {
+            if self.normalize {
+                variant.left_align(self.sequences);
+            }
+
+            return Some(self.annotate(variant));
+        }
+
+        self.ref_mismatch_count
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        match self.ref_mismatch {
+            RefMismatch::Warn => {
+                log::warn!(
+                    "Reference sequence of variant {} didn't match genome",
+                    variant
+                );
+                Some(self.annotate(variant))
+            }
+            RefMismatch::Flag => {
+                let mut myth = self.annotate(variant);
+                myth.annotations
+                    .iter_mut()
+                    .for_each(|a| a.warning = myth::WARNING_REF_MISMATCH.to_vec());
+                Some(myth)
+            }
+            RefMismatch::Skip => {
+                log::warn!(
+                    "Reference sequence of variant {} didn't match genome, variant is skip",
+                    variant
+                );
+                None
+            }
+        }
+    }
+
+    /// Remove transcript annotations not selected, annotations not associate to a transcript are keep
+    fn select(&self, myth: &mut myth::Myth) {
+        match &self.transcript_selection {
+            TranscriptSelection::All => (),
+            TranscriptSelection::MostSevere => {
+                // For each gene, index of best annotation
+                let mut best: ahash::AHashMap<&[u8], usize> = ahash::AHashMap::new();
+                for (index, annotation) in myth.annotations.iter().enumerate() {
+                    let Some(transcript) = self.annotations.get_transcript(&annotation.id) else {
+                        continue;
+                    };
+                    let gene = match transcript.get_parent() {
+                        b"" => annotation.id.as_slice(),
+                        parent => parent,
+                    };
+
+                    best.entry(gene)
+                        .and_modify(|i| {
+                            if self.more_severe(annotation, &myth.annotations[*i]) {
+                                *i = index
+                            }
+                        })
+                        .or_insert(index);
+                }
+
+                let keep = best.into_values().collect::<ahash::AHashSet<usize>>();
+                myth.annotations = std::mem::take(&mut myth.annotations)
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, annotation)| {
+                        keep.contains(index)
+                            || self.annotations.get_transcript(&annotation.id).is_none()
+                    })
+                    .map(|(_, annotation)| annotation)
+                    .collect();
+            }
+            TranscriptSelection::Canonical => myth.annotations.retain(|annotation| {
+                self.annotations
+                    .get_transcript(&annotation.id)
+                    .map_or(true, |transcript| self.canonical(transcript))
+            }),
+            TranscriptSelection::Allowlist(allowlist) => myth.annotations.retain(|annotation| {
+                self.annotations.get_transcript(&annotation.id).is_none()
+                    || allowlist.contains(&annotation.id)
+                    || annotation
+                        .id
+                        .rsplit_once_str(".")
+                        .is_some_and(|(id, _version)| allowlist.contains(id))
+            }),
+        }
+    }
+
+    /// Transcript is tagged as canonical
+    fn canonical(&self, transcript: &annotation::Annotation) -> bool {
+        CANONICAL_TAGS
+            .iter()
+            .any(|tag| transcript.get_attribute().has_tag(tag))
+    }
+
+    /// Annotation a is more severe than b, on equal impact canonical transcript and after smallest id are prefer
+    fn more_severe(&self, a: &myth::AnnotationMyth, b: &myth::AnnotationMyth) -> bool {
+        let canonical = |annotation: &myth::AnnotationMyth| {
+            self.annotations
+                .get_transcript(&annotation.id)
+                .is_some_and(|transcript| self.canonical(transcript))
+        };
+
+        (&a.impact, canonical(a), std::cmp::Reverse(&a.id))
+            > (&b.impact, canonical(b), std::cmp::Reverse(&b.id))
+    }
+
+    /// Get value of selected attribute keys, from transcript and if missing from gene
+    fn copy_attributes(&self, transcript_id: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let transcript = self.annotations.get_transcript(transcript_id);
+        let gene = transcript.and_then(|t| self.annotations.get_gene(t.get_parent()));
+
+        self.attributes
+            .iter()
+            .filter_map(|key| {
+                [transcript, gene]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|annotation| annotation.get_attribute().get(key))
+                    .filter(|value| !value.is_empty())
+                    .map(|value| (key.clone(), value.to_vec()))
+            })
+            .collect()
+    }
+
+    fn annotate(&self, variant: variant::Variant) -> myth::Myth {
         let mut myth = myth::Myth::from_variant(variant.clone());
 
         // Ignore not variant we could manage
@@ -135,30 +518,19 @@ impl<'a> Variant2Myth<'a> {
             myth
         );
 
-        // Add myth with gene associate information
-        if self.annotators_choices.contains(AnnotatorsChoicesRaw::Gene) {
-            for gene in not_coding_annotations
-                .iter()
-                .filter(|a| a.get_feature() == b"gene")
-            {
-                myth.add_annotation(
-                    myth::AnnotationMyth::from_annotation(gene)
-                        .effects(vec![])
-                        .build()
-                        .unwrap(), // No possible error in build
-                );
-            }
-        }
-
-        // Get unique transcript
-        let mut transcripts = ahash::AHashSet::new();
-        for annotation in not_coding_annotations.iter() {
-            if annotation.get_feature() == b"transcript" {
-                transcripts.insert(annotation.get_attribute().get_id())
-            } else {
-                transcripts.insert(annotation.get_attribute().get_parent())
-            };
-        }
+        // Get unique transcript, sort to get same output order on each run
+        let mut transcripts = not_coding_annotations
+            .iter()
+            .map(|annotation| {
+                if annotation.is_transcript() {
+                    annotation.get_attribute().get_id()
+                } else {
+                    annotation.get_attribute().get_parent()
+                }
+            })
+            .collect::<Vec<&[u8]>>();
+        transcripts.sort_unstable();
+        transcripts.dedup();
 
         for transcript_id in transcripts.iter() {
             let mut memoizor = memoizor::Memoizor::new(
@@ -178,9 +550,11 @@ impl<'a> Variant2Myth<'a> {
             for flag in self.annotators_choices.iter() {
                 self.annotators[(flag as u8).ilog2() as usize]
                     .iter()
-                    .for_each(|a| {
-                        annotation_myth.extend_effect(&a.annotate(&variant, &mut memoizor));
-                    })
+                    .for_each(|a| a.annotate_myth(&variant, &mut memoizor, &mut annotation_myth))
+            }
+
+            if !self.attributes.is_empty() {
+                annotation_myth.set_attributes(self.copy_attributes(transcript_id));
             }
 
             myth.add_annotation(annotation_myth.build().unwrap()) // No possible error in build
@@ -274,7 +648,333 @@ mod tests {
                 .unwrap(),
         );
 
-        assert_eq!(variant2myth.myth(variant), truth);
+        assert_eq!(variant2myth.myth(variant), Some(truth));
+
+        Ok(())
+    }
+    #[test]
+    fn ref_mismatch() -> error::Result<()> {
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::GFF));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+
+        let translate = translate::Translate::default();
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::SEQUENCE));
+        let sequences_db = sequences_db::SequencesDataBase::from_reader(reader)?;
+
+        let variant = variant::Variant::test_variant(b"chrA", 247, b"GT", b"G", None)?;
+
+        let variant2myth = Variant2Myth::new(
+            &annotations_db,
+            &translate,
+            &sequences_db,
+            AnnotatorsChoices::empty(),
+        )
+        .ref_mismatch(RefMismatch::Flag);
+        let myth = variant2myth.myth(variant.clone()).unwrap();
+        assert!(!myth.annotations.is_empty());
+        assert!(myth
+            .annotations
+            .iter()
+            .all(|a| a.warning == myth::WARNING_REF_MISMATCH));
+        assert_eq!(variant2myth.ref_mismatch_count(), 1);
+
+        let variant2myth = Variant2Myth::new(
+            &annotations_db,
+            &translate,
+            &sequences_db,
+            AnnotatorsChoices::empty(),
+        )
+        .ref_mismatch(RefMismatch::Skip);
+        assert_eq!(variant2myth.myth(variant), None);
+        let myth = variant2myth
+            .myth(variant::Variant::test_variant(
+                b"chrA", 247, b"TT", b"T", None,
+            )?)
+            .unwrap();
+        assert!(myth.annotations.iter().all(|a| a.warning.is_empty()));
+        assert_eq!(variant2myth.ref_mismatch_count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vcf")]
+    fn ref_mismatch_multi_allelic() -> error::Result<()> {
+        use crate::output::MythWriter as _;
+        use bstr::ByteSlice as _;
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::GFF));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+
+        let translate = translate::Translate::default();
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::SEQUENCE));
+        let sequences_db = sequences_db::SequencesDataBase::from_reader(reader)?;
+
+        let variant2myth = Variant2Myth::new(
+            &annotations_db,
+            &translate,
+            &sequences_db,
+            AnnotatorsChoices::empty(),
+        )
+        .ref_mismatch(RefMismatch::Skip);
+
+        // REF didn't match genome, C is skip but * can't be check
+        let variants = variant::Variant::from_byte_record(csv::ByteRecord::from(vec![
+            "chrA", "248", ".", "G", "C,*", ".", ".", ".", "GT", "1/2",
+        ]))?;
+        let myths = variants
+            .into_iter()
+            .filter_map(|variant| variant2myth.myth(variant))
+            .collect::<Vec<myth::Myth>>();
+        assert_eq!(myths.len(), 1);
+        assert_eq!(myths[0].variant.allele_index, 1);
+        assert_eq!(variant2myth.ref_mismatch_count(), 1);
+
+        let mut output = Vec::new();
+        let mut writer = crate::output::VcfWriter::new(&mut output)?;
+        for myth in myths {
+            writer.write_myth(myth)?;
+        }
+        writer.write_myth(myth::Myth::from_variant(variant::Variant::test_variant(
+            b"chrA", 300, b"A", b"C", None,
+        )?))?;
+        writer.close()?;
+        drop(writer);
+
+        let records = output
+            .lines()
+            .filter(|line| !line.starts_with(b"#"))
+            .collect::<Vec<&[u8]>>();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].starts_with(b"chrA\t248\t.\tG\tC,*\t.\t.\tANN=*|"));
+        assert!(records[0].ends_with(b"\tGT\t1/2"));
+        assert!(records[1].starts_with(b"chrA\t301\t"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bad_record() -> error::Result<()> {
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::GFF));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+
+        let translate = translate::Translate::default();
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::SEQUENCE));
+        let sequences_db = sequences_db::SequencesDataBase::from_reader(reader)?;
+
+        let invalid = || -> error::Result<variant::Variant> {
+            Err(error::Error::VcfInvalidRecord {
+                line: 3,
+                record: "chrA\tten\t.\tA\tT".to_string(),
+                message: "invalid digit found in string".to_string(),
+            }
+            .into())
+        };
+        let build = |bad_record| {
+            Variant2Myth::new(
+                &annotations_db,
+                &translate,
+                &sequences_db,
+                AnnotatorsChoices::empty(),
+            )
+            .bad_record(bad_record)
+        };
+
+        let variant2myth = build(BadRecord::Fail);
+        assert!(variant2myth.record(invalid()).is_err());
+        assert!(variant2myth
+            .record(Err(error::Error::VcfBadRecord.into()))
+            .is_err());
+        assert!(variant2myth
+            .record(variant::Variant::test_variant(
+                b"chrA", 247, b"TT", b"T", None
+            ))?
+            .is_some());
+        assert_eq!(variant2myth.bad_record_count(), 0);
+        assert_eq!(variant2myth.variants_count(), 1);
+
+        let variant2myth = build(BadRecord::Skip);
+        assert!(variant2myth.record(invalid())?.is_none());
+        assert!(variant2myth
+            .record(Err(error::Error::VcfBadRecord.into()))
+            .is_err());
+        assert_eq!(variant2myth.bad_record_count(), 1);
+
+        let variant2myth = build(BadRecord::Placeholder);
+        let mut truth =
+            myth::Myth::from_variant(variant::Variant::from_invalid_record(b"chrA\tten\t.\tA\tT"));
+        truth.add_annotation(
+            myth::AnnotationMyth::from_nowhere()
+                .effects(vec![effect::Effect::InvalidRecord])
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(variant2myth.record(invalid())?, Some(truth));
+        assert_eq!(
+            variant2myth.counts(),
+            Counts {
+                variants: 0,
+                ref_mismatch: 0,
+                bad_record: 1
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn transcript_selection() -> error::Result<()> {
+        let gff: &[u8] = b"chrA\ttest\tgene\t51\t300\t.\t+\t.\tID=g1
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr1;Parent=g1;tag=basic
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr2;Parent=g1;tag=basic,Ensembl_canonical
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr3;Parent=g2;tag=MANE_Select
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr4.1
+";
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(gff));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+
+        let translate = translate::Translate::default();
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::SEQUENCE));
+        let sequences_db = sequences_db::SequencesDataBase::from_reader(reader)?;
+
+        let build_myth = |effects: &[(&[u8], effect::Effect)]| -> error::Result<myth::Myth> {
+            let mut myth = myth::Myth::from_variant(variant::Variant::test_variant(
+                b"chrA", 100, b"A", b"C", None,
+            )?);
+            for (id, effect) in effects {
+                let mut annotation = myth::AnnotationMyth::builder()
+                    .source(b"test".to_vec())
+                    .feature(b"transcript".to_vec())
+                    .id(id.to_vec());
+                annotation.add_effect(effect.clone());
+                myth.add_annotation(annotation.build()?);
+            }
+            Ok(myth)
+        };
+        let ids = |myth: &myth::Myth| {
+            myth.annotations
+                .iter()
+                .map(|a| a.id.clone())
+                .collect::<Vec<Vec<u8>>>()
+        };
+
+        let effects: Vec<(&[u8], effect::Effect)> = vec![
+            (b"tr1", effect::Effect::StopGained),
+            (b"tr2", effect::Effect::IntronVariant),
+            (b"tr3", effect::Effect::SynonymousVariant),
+            (b"tr4.1", effect::Effect::IntronVariant),
+            (b"unknow", effect::Effect::IntronVariant),
+        ];
+
+        let truth: Vec<(TranscriptSelection, Vec<&[u8]>)> = vec![
+            (
+                TranscriptSelection::All,
+                vec![b"tr1", b"tr2", b"tr3", b"tr4.1", b"unknow"],
+            ),
+            (
+                TranscriptSelection::MostSevere,
+                vec![b"tr1", b"tr3", b"tr4.1", b"unknow"],
+            ),
+            (
+                TranscriptSelection::Canonical,
+                vec![b"tr2", b"tr3", b"unknow"],
+            ),
+            (
+                TranscriptSelection::Allowlist(
+                    [b"tr1".to_vec(), b"tr4".to_vec()].into_iter().collect(),
+                ),
+                vec![b"tr1", b"tr4.1", b"unknow"],
+            ),
+        ];
+
+        for (selection, keep) in truth {
+            let variant2myth = Variant2Myth::new(
+                &annotations_db,
+                &translate,
+                &sequences_db,
+                AnnotatorsChoices::empty(),
+            )
+            .transcript_selection(selection);
+
+            let mut myth = build_myth(&effects)?;
+            variant2myth.select(&mut myth);
+            assert_eq!(ids(&myth), keep);
+        }
+
+        // With same impact canonical transcript is prefer
+        let variant2myth = Variant2Myth::new(
+            &annotations_db,
+            &translate,
+            &sequences_db,
+            AnnotatorsChoices::empty(),
+        )
+        .transcript_selection(TranscriptSelection::MostSevere);
+        let mut myth = build_myth(&[
+            (b"tr1", effect::Effect::IntronVariant),
+            (b"tr2", effect::Effect::IntronVariant),
+        ])?;
+        variant2myth.select(&mut myth);
+        assert_eq!(ids(&myth), vec![b"tr2".to_vec()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn attributes() -> error::Result<()> {
+        let gff: &[u8] = b"chrA\ttest\tgene\t51\t300\t.\t+\t.\tID=g1;gene_type=lncRNA;level=2
+chrA\ttest\ttranscript\t51\t300\t.\t+\t.\tID=tr1;Parent=g1;level=1;note=a%3Bb
+chrA\ttest\texon\t51\t300\t.\t+\t.\tID=exon1;Parent=tr1;exon_type=first
+";
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(gff));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+
+        let translate = translate::Translate::default();
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::SEQUENCE));
+        let sequences_db = sequences_db::SequencesDataBase::from_reader(reader)?;
+
+        let variant = variant::Variant::test_variant(b"chrA", 100, b"A", b"C", None)?;
+
+        let variant2myth = Variant2Myth::new(
+            &annotations_db,
+            &translate,
+            &sequences_db,
+            AnnotatorsChoices::empty(),
+        );
+        let myth = variant2myth.myth(variant.clone()).unwrap();
+        assert!(myth.annotations.iter().all(|a| a.attributes.is_empty()));
+
+        let variant2myth = variant2myth.attributes(vec![
+            b"level".to_vec(),
+            b"gene_type".to_vec(),
+            b"note".to_vec(),
+            b"exon_type".to_vec(),
+            b"missing".to_vec(),
+        ]);
+        let myth = variant2myth.myth(variant).unwrap();
+        assert_eq!(myth.annotations.len(), 1);
+        assert_eq!(
+            myth.annotations[0].attributes,
+            vec![
+                (b"level".to_vec(), b"1".to_vec()),
+                (b"gene_type".to_vec(), b"lncRNA".to_vec()),
+                (b"note".to_vec(), b"a;b".to_vec()),
+            ]
+        );
 
         Ok(())
     }
@@ -0,0 +1,166 @@
+//! An annotator for breakend joining two genes
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::annotation;
+use crate::annotations_db;
+use crate::effect;
+use crate::memoizor;
+use crate::variant;
+use crate::variant2myth;
+
+pub struct Fusion<'a> {
+    annotations: &'a annotations_db::AnnotationsDataBase,
+}
+
+impl<'a> Fusion<'a> {
+    pub const fn new(annotations: &'a annotations_db::AnnotationsDataBase) -> Self {
+        Self { annotations }
+    }
+}
+
+impl Fusion<'_> {
+    /// Get genes, with transcript that overlap mate position
+    fn mate_genes(&self, mate: &variant::Breakend) -> Vec<&annotation::Annotation> {
+        let interval = mate.position..mate.position + 1;
+
+        let mut genes: Vec<&annotation::Annotation> = vec![];
+        for annotation in self
+            .annotations
+            .get_annotations(&mate.seqname, interval.clone())
+        {
+            let transcript_id = if annotation.is_transcript() {
+                annotation.get_attribute().get_id()
+            } else {
+                annotation.get_attribute().get_parent()
+            };
+
+            let gene = self
+                .annotations
+                .get_transcript(transcript_id)
+                .filter(|transcript| variant2myth::overlap(&interval, &transcript.get_interval()))
+                .and_then(|transcript| self.annotations.get_gene(transcript.get_parent()));
+
+            if let Some(gene) = gene {
+                if !genes
+                    .iter()
+                    .any(|g| g.get_attribute().get_id() == gene.get_attribute().get_id())
+                {
+                    genes.push(gene);
+                }
+            }
+        }
+
+        genes
+    }
+}
+
+impl variant2myth::Annotator for Fusion<'_> {
+    fn annotate(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+    ) -> Vec<effect::Effect> {
+        let mate = match &variant.variant_type {
+            variant::Type::Bnd(mate) => mate,
+            _ => return vec![],
+        };
+
+        let interval = variant.get_interval();
+        match memoizor.transcript() {
+            Some(transcript) if variant2myth::overlap(&interval, &transcript.get_interval()) => (),
+            _ => return vec![],
+        }
+        let (gene_id, strand) = match memoizor.gene() {
+            Some(gene) => (gene.get_attribute().get_id().to_vec(), *gene.get_strand()),
+            None => return vec![],
+        };
+
+        let mut effects = vec![];
+        for gene in self.mate_genes(mate) {
+            if gene.get_attribute().get_id() == gene_id {
+                continue;
+            }
+
+            // Junction build a sense fusion transcript if genes orientation match junction orientation
+            let effect = if (*gene.get_strand() == strand) == mate.same_orientation() {
+                effect::Effect::GeneFusion
+            } else {
+                effect::Effect::BidirectionalGeneFusion
+            };
+
+            if !effects.contains(&effect) {
+                effects.push(effect);
+            }
+        }
+
+        effects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use crate::effect;
+    use crate::error;
+    use crate::memoizor;
+    use crate::test_data;
+    use crate::variant;
+    use crate::variant2myth::Annotator as _;
+
+    use super::*;
+
+    #[test]
+    fn fusion() -> error::Result<()> {
+        let annotations_db = &*test_data::TRANSCRIPTS_DB;
+
+        let obj = Fusion::new(annotations_db);
+
+        let truth: Vec<(&[u8], Vec<effect::Effect>)> = vec![
+            // same strand, same orientation
+            (b"A[chrA:1500[", vec![effect::Effect::GeneFusion]),
+            (
+                b"A]chrA:1500]",
+                vec![effect::Effect::BidirectionalGeneFusion],
+            ),
+            // opposite strand, mate is reverse complemented
+            (b"A]chrB:1500]", vec![effect::Effect::GeneFusion]),
+            (b"[chrB:1500[A", vec![effect::Effect::GeneFusion]),
+            (
+                b"]chrB:1500]A",
+                vec![effect::Effect::BidirectionalGeneFusion],
+            ),
+            // mate in same gene or intergenic
+            (b"A[chrA:200[", vec![]),
+            (b"A[chrA:5000[", vec![]),
+        ];
+
+        for (alt, effects) in truth {
+            let variant = variant::Variant::test_variant(b"chrA", 100, b"A", alt, None)?;
+            let mut memoizor =
+                memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+
+            assert_eq!(
+                obj.annotate(&variant, &mut memoizor),
+                effects,
+                "{}",
+                String::from_utf8_lossy(alt)
+            );
+        }
+
+        // Breakend outside of transcript
+        let variant = variant::Variant::test_variant(b"chrA", 500, b"A", b"A[chrA:1500[", None)?;
+        let mut memoizor =
+            memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+        assert_eq!(obj.annotate(&variant, &mut memoizor), vec![]);
+
+        Ok(())
+    }
+}
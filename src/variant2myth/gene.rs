@@ -0,0 +1,115 @@
+//! An annotator that link transcript to its gene
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::effect;
+use crate::memoizor;
+use crate::myth;
+use crate::variant;
+use crate::variant2myth;
+
+pub struct Gene;
+
+impl Gene {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl variant2myth::Annotator for Gene {
+    fn annotate(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+    ) -> Vec<effect::Effect> {
+        let interval = variant.get_interval();
+
+        if memoizor
+            .transcript()
+            .is_some_and(|transcript| variant2myth::overlap(&interval, &transcript.get_interval()))
+        {
+            vec![effect::Effect::GeneVariant]
+        } else if memoizor
+            .gene()
+            .is_some_and(|gene| variant2myth::overlap(&interval, &gene.get_interval()))
+        {
+            vec![effect::Effect::IntragenicVariant]
+        } else {
+            vec![]
+        }
+    }
+
+    fn annotate_myth(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+        annotation_myth: &mut myth::AnnotationMythBuilder,
+    ) {
+        if let Some(gene) = memoizor.gene() {
+            annotation_myth.set_gene(
+                gene.get_attribute().get_id().to_vec(),
+                gene.get_attribute().get_name().to_vec(),
+            );
+        }
+
+        annotation_myth.extend_effect(&self.annotate(variant, memoizor));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use crate::effect;
+    use crate::error;
+    use crate::memoizor;
+    use crate::myth;
+    use crate::test_data;
+    use crate::variant;
+    use crate::variant2myth::Annotator as _;
+
+    use super::*;
+
+    #[test]
+    fn gene() -> error::Result<()> {
+        let annotations_db = &*test_data::TRANSCRIPTS_DB;
+
+        let obj = Gene::new();
+
+        let truth: Vec<(&[u8], u64, Vec<effect::Effect>)> = vec![
+            (b"tr1", 100, vec![effect::Effect::GeneVariant]),
+            (b"tr1", 500, vec![effect::Effect::IntragenicVariant]),
+            (b"tr1", 10, vec![]),
+            (b"tr2", 100, vec![effect::Effect::GeneVariant]),
+            (b"tr2", 500, vec![]),
+        ];
+
+        for (transcript, position, effects) in truth {
+            let variant = variant::Variant::test_variant(b"chrA", position, b"A", b"C", None)?;
+            let mut memoizor =
+                memoizor::Memoizor::new(transcript, annotations_db, &test_data::SEQUENCE_DB, &[]);
+
+            assert_eq!(obj.annotate(&variant, &mut memoizor), effects);
+        }
+
+        let variant = variant::Variant::test_variant(b"chrA", 100, b"A", b"C", None)?;
+        let mut memoizor =
+            memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+        let mut annotation_myth =
+            myth::AnnotationMyth::from_annotation(memoizor.transcript().unwrap()).effects(vec![]);
+        obj.annotate_myth(&variant, &mut memoizor, &mut annotation_myth);
+        let annotation = annotation_myth.build()?;
+
+        assert_eq!(annotation.gene_id, b"g1");
+        assert_eq!(annotation.gene_name, b"gene1");
+        assert_eq!(annotation.effects, vec![effect::Effect::GeneVariant]);
+
+        Ok(())
+    }
+}
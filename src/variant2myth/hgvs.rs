@@ -0,0 +1,716 @@
+//! An annotator to build HGVS nomenclature of variant
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::annotation;
+use crate::effect;
+use crate::memoizor;
+use crate::myth;
+use crate::sequences_db;
+use crate::translate;
+use crate::variant;
+use crate::variant2myth;
+
+/// Get three letter code of an amino acid
+fn three_letter(aa: u8) -> &'static str {
+    match aa.to_ascii_uppercase() {
+        b'A' => "Ala",
+        b'R' => "Arg",
+        b'N' => "Asn",
+        b'D' => "Asp",
+        b'C' => "Cys",
+        b'Q' => "Gln",
+        b'E' => "Glu",
+        b'G' => "Gly",
+        b'H' => "His",
+        b'I' => "Ile",
+        b'L' => "Leu",
+        b'K' => "Lys",
+        b'M' => "Met",
+        b'F' => "Phe",
+        b'P' => "Pro",
+        b'S' => "Ser",
+        b'T' => "Thr",
+        b'W' => "Trp",
+        b'Y' => "Tyr",
+        b'V' => "Val",
+        b'U' => "Sec",
+        b'O' => "Pyl",
+        b'*' => "Ter",
+        _ => "Xaa",
+    }
+}
+
+/// Get three letter code of a peptide
+fn three_letters(peptide: &[u8]) -> String {
+    peptide.iter().map(|aa| three_letter(*aa)).collect()
+}
+
+/// Convert a nucleotide sequence in upper case string
+fn upper(seq: &[u8]) -> String {
+    String::from_utf8_lossy(seq).to_ascii_uppercase()
+}
+
+/// Format a range, if first and last are equal only one value is write
+fn range<T>(first: T, last: T) -> String
+where
+    T: std::fmt::Display + std::cmp::PartialEq,
+{
+    if first == last {
+        format!("{}", first)
+    } else {
+        format!("{}_{}", first, last)
+    }
+}
+
+/// Remove common prefix and suffix of reference and alternative sequence, position of first changed base is return
+fn trim<'b>(position: u64, ref_seq: &'b [u8], alt_seq: &'b [u8]) -> (u64, &'b [u8], &'b [u8]) {
+    let prefix = ref_seq
+        .iter()
+        .zip(alt_seq.iter())
+        .take_while(|(r, a)| r.eq_ignore_ascii_case(a))
+        .count();
+    let (ref_seq, alt_seq) = (&ref_seq[prefix..], &alt_seq[prefix..]);
+
+    let suffix = ref_seq
+        .iter()
+        .rev()
+        .zip(alt_seq.iter().rev())
+        .take_while(|(r, a)| r.eq_ignore_ascii_case(a))
+        .count();
+
+    (
+        position + prefix as u64,
+        &ref_seq[..ref_seq.len() - suffix],
+        &alt_seq[..alt_seq.len() - suffix],
+    )
+}
+
+/// Convert a genomic position in a position that grows in transcript orientation
+fn orient(position: u64, strand: annotation::Strand) -> i64 {
+    match strand {
+        annotation::Strand::Forward => position as i64,
+        annotation::Strand::Reverse => -(position as i64),
+    }
+}
+
+/// Get transcript position (1-based) and intronic offset of an oriented position
+fn locate(exons: &[(i64, i64)], position: i64) -> (i64, i64) {
+    let mut length = 0;
+
+    for (index, (start, end)) in exons.iter().enumerate() {
+        if position < *start {
+            if index == 0 {
+                // before transcript
+                return (1 - (start - position), 0);
+            }
+
+            // in intron, position is relative to the nearest exon
+            let previous_end = exons[index - 1].1;
+            let upstream = position - (previous_end - 1);
+            let downstream = start - position;
+
+            return if upstream <= downstream {
+                (length, upstream)
+            } else {
+                (length + 1, -downstream)
+            };
+        }
+
+        if position < *end {
+            return (length + (position - start) + 1, 0);
+        }
+
+        length += end - start;
+    }
+
+    // after transcript
+    let last_end = exons.last().map(|(_, end)| *end).unwrap_or(0);
+    (length + (position - (last_end - 1)), 0)
+}
+
+/// Format a transcript position in c. or n. coordinate
+fn format_position(position: i64, offset: i64, coding: Option<(i64, i64)>, length: i64) -> String {
+    let base = match coding {
+        Some((coding_start, coding_end)) => {
+            if position < coding_start {
+                format!("-{}", coding_start - position)
+            } else if position > coding_end {
+                format!("*{}", position - coding_end)
+            } else {
+                format!("{}", position - coding_start + 1)
+            }
+        }
+        None => {
+            if position < 1 {
+                format!("-{}", 1 - position)
+            } else if position > length {
+                format!("*{}", position - length)
+            } else {
+                format!("{}", position)
+            }
+        }
+    };
+
+    match offset.cmp(&0) {
+        std::cmp::Ordering::Greater => format!("{}+{}", base, offset),
+        std::cmp::Ordering::Less => format!("{}{}", base, offset),
+        std::cmp::Ordering::Equal => base,
+    }
+}
+
+/// Format a range of amino acid, first and last are 0-based index
+fn protein_range(protein: &[u8], first: usize, last: usize) -> String {
+    if first == last {
+        format!("{}{}", three_letter(protein[first]), first + 1)
+    } else {
+        format!(
+            "{}{}_{}{}",
+            three_letter(protein[first]),
+            first + 1,
+            three_letter(protein[last]),
+            last + 1
+        )
+    }
+}
+
+pub struct Hgvs<'a> {
+    translate: &'a translate::Translate,
+    sequences: &'a sequences_db::SequencesDataBase,
+}
+
+impl<'a> Hgvs<'a> {
+    pub const fn new(
+        translate: &'a translate::Translate,
+        sequences: &'a sequences_db::SequencesDataBase,
+    ) -> Self {
+        Self {
+            translate,
+            sequences,
+        }
+    }
+
+    /// Reference sequence match sequence at interval
+    fn reference_match(&self, seqname: &[u8], interval: core::ops::Range<u64>, seq: &[u8]) -> bool {
+        self.sequences
+            .get_interval(seqname, &interval)
+            .map(|reference| reference.eq_ignore_ascii_case(seq))
+            .unwrap_or(false)
+    }
+
+    /// Build HGVS genomic description
+    fn genomic(&self, variant: &variant::Variant) -> Option<String> {
+        let description = match variant.variant_type {
+            variant::Type::Small => {
+                let (start, ref_seq, alt_seq) =
+                    trim(variant.position, &variant.ref_seq, &variant.alt_seq);
+                let end = start + ref_seq.len() as u64;
+
+                match (ref_seq.len(), alt_seq.len()) {
+                    (0, 0) => return None,
+                    (1, 1) => format!("{}{}>{}", start + 1, upper(ref_seq), upper(alt_seq)),
+                    (_, 0) => format!("{}del", range(start + 1, end)),
+                    (0, length) => {
+                        let length = length as u64;
+                        if start >= length
+                            && self.reference_match(
+                                &variant.seqname,
+                                start - length..start,
+                                alt_seq,
+                            )
+                        {
+                            format!("{}dup", range(start - length + 1, start))
+                        } else {
+                            format!("{}_{}ins{}", start, start + 1, upper(alt_seq))
+                        }
+                    }
+                    (_, _) => format!("{}delins{}", range(start + 1, end), upper(alt_seq)),
+                }
+            }
+            variant::Type::Del(length) => format!(
+                "{}del",
+                range(variant.position + 2, variant.position + 1 + length)
+            ),
+            variant::Type::Dup(length) => format!(
+                "{}dup",
+                range(variant.position + 2, variant.position + 1 + length)
+            ),
+            variant::Type::Inv(length) => format!(
+                "{}inv",
+                range(variant.position + 2, variant.position + 1 + length)
+            ),
+            _ => return None,
+        };
+
+        Some(format!(
+            "{}:g.{}",
+            String::from_utf8_lossy(&variant.seqname),
+            description
+        ))
+    }
+
+    /// Build HGVS transcript description
+    fn transcript(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+    ) -> Option<String> {
+        if variant.structural() {
+            return None;
+        }
+
+        let strand = memoizor.strand();
+
+        let mut exons = memoizor
+            .exons_annotation()
+            .iter()
+            .map(|a| match strand {
+                annotation::Strand::Forward => (a.get_start() as i64, a.get_stop() as i64),
+                annotation::Strand::Reverse => {
+                    (-(a.get_stop() as i64) + 1, -(a.get_start() as i64) + 1)
+                }
+            })
+            .collect::<Vec<(i64, i64)>>();
+        exons.sort();
+
+        if exons.is_empty() {
+            return None;
+        }
+
+        let length = exons.iter().map(|(start, end)| end - start).sum();
+        let coding = memoizor.coding_interval().map(|interval| {
+            let (first, last) = match strand {
+                annotation::Strand::Forward => (interval.start, interval.end - 1),
+                annotation::Strand::Reverse => (interval.end - 1, interval.start),
+            };
+
+            (
+                locate(&exons, orient(first, strand)).0,
+                locate(&exons, orient(last, strand)).0,
+            )
+        });
+
+        let position = |genomic: u64| {
+            let (position, offset) = locate(&exons, orient(genomic, strand));
+            format_position(position, offset, coding, length)
+        };
+        let span = |first: u64, last: u64| match strand {
+            annotation::Strand::Forward => range(position(first), position(last)),
+            annotation::Strand::Reverse => range(position(last), position(first)),
+        };
+
+        let (start, ref_seq, alt_seq) = trim(variant.position, &variant.ref_seq, &variant.alt_seq);
+        let end = start + ref_seq.len() as u64;
+
+        let mut alt_oriented = alt_seq.to_vec();
+        let mut ref_oriented = ref_seq.to_vec();
+        if strand == annotation::Strand::Reverse {
+            sequences_db::rev_comp(&mut alt_oriented);
+            sequences_db::rev_comp(&mut ref_oriented);
+        }
+
+        let description = match (ref_seq.len(), alt_seq.len()) {
+            (0, 0) => return None,
+            (1, 1) => format!(
+                "{}{}>{}",
+                position(start),
+                upper(&ref_oriented),
+                upper(&alt_oriented)
+            ),
+            (_, 0) => format!("{}del", span(start, end - 1)),
+            (0, length) => {
+                let length = length as u64;
+                let duplicate = match strand {
+                    annotation::Strand::Forward => (start >= length
+                        && self.reference_match(&variant.seqname, start - length..start, alt_seq))
+                    .then(|| span(start - length, start - 1)),
+                    annotation::Strand::Reverse => self
+                        .reference_match(&variant.seqname, start..start + length, alt_seq)
+                        .then(|| span(start, start + length - 1)),
+                };
+
+                if let Some(duplicate) = duplicate {
+                    format!("{}dup", duplicate)
+                } else if start == 0 {
+                    return None;
+                } else {
+                    format!("{}ins{}", span(start - 1, start), upper(&alt_oriented))
+                }
+            }
+            (_, _) => format!("{}delins{}", span(start, end - 1), upper(&alt_oriented)),
+        };
+
+        if coding.is_some() {
+            Some(format!("c.{}", description))
+        } else {
+            Some(format!("n.{}", description))
+        }
+    }
+
+    /// Build HGVS protein description
+    ///
+    /// Only coding sequence is translate, 3' UTR isn't read: if new stop codon of a frameshift is
+    /// after end of CDS its position is unknown and description end with `fsTer?`.
+    fn protein(&self, coding: &[u8], coding_var: &[u8]) -> Option<String> {
+        let first_diff = coding
+            .iter()
+            .zip(coding_var.iter())
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+
+        if first_diff == coding.len() && coding.len() == coding_var.len() {
+            return None;
+        }
+
+        // Start codon is lost
+        if first_diff < 3
+            && coding.len() >= 3
+            && self.translate.is_start(&coding[..3])
+            && !(coding_var.len() >= 3 && self.translate.is_start(&coding_var[..3]))
+        {
+            return Some("p.Met1?".to_string());
+        }
+
+        let protein = self.translate.translate(coding);
+        let protein_var = self.translate.translate(coding_var);
+
+        let stop = protein.iter().position(|aa| *aa == b'*');
+        let stop_var = protein_var.iter().position(|aa| *aa == b'*');
+
+        let protein = &protein[..stop.map(|s| s + 1).unwrap_or(protein.len())];
+        let protein_var = &protein_var[..stop_var.map(|s| s + 1).unwrap_or(protein_var.len())];
+
+        let first_codon = first_diff / 3;
+        if first_codon >= protein.len() {
+            // variant after stop codon
+            return None;
+        }
+
+        let prefix = protein
+            .iter()
+            .zip(protein_var.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let length_diff = coding_var.len() as i64 - coding.len() as i64;
+        if length_diff % 3 != 0 {
+            if prefix >= protein.len() {
+                return None;
+            }
+
+            return Some(match (protein_var.get(prefix), stop_var) {
+                (Some(b'*'), _) => format!("p.{}{}Ter", three_letter(protein[prefix]), prefix + 1),
+                (Some(aa), Some(stop_var)) => format!(
+                    "p.{}{}{}fsTer{}",
+                    three_letter(protein[prefix]),
+                    prefix + 1,
+                    three_letter(*aa),
+                    stop_var - prefix + 1
+                ),
+                (Some(aa), None) => format!(
+                    "p.{}{}{}fsTer?",
+                    three_letter(protein[prefix]),
+                    prefix + 1,
+                    three_letter(*aa)
+                ),
+                (None, _) => format!("p.{}{}fs", three_letter(protein[prefix]), prefix + 1),
+            });
+        }
+
+        // Synonymous
+        if protein == protein_var {
+            return Some(format!(
+                "p.{}{}=",
+                three_letter(protein[first_codon]),
+                first_codon + 1
+            ));
+        }
+
+        // Stop codon is lost
+        if stop == Some(prefix) {
+            return Some(match (protein_var.get(prefix), stop_var) {
+                (Some(aa), Some(stop_var)) => format!(
+                    "p.Ter{}{}extTer{}",
+                    prefix + 1,
+                    three_letter(*aa),
+                    stop_var - prefix + 1
+                ),
+                (Some(aa), None) => format!("p.Ter{}{}extTer?", prefix + 1, three_letter(*aa)),
+                (None, _) => format!("p.Ter{}?", prefix + 1),
+            });
+        }
+
+        // Stop codon is gained
+        if stop_var == Some(prefix) {
+            return Some(format!(
+                "p.{}{}Ter",
+                three_letter(protein[prefix]),
+                prefix + 1
+            ));
+        }
+
+        let suffix = protein[prefix..]
+            .iter()
+            .rev()
+            .zip(protein_var[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let deleted = &protein[prefix..protein.len() - suffix];
+        let inserted = &protein_var[prefix..protein_var.len() - suffix];
+
+        Some(match (deleted.len(), inserted.len()) {
+            (0, 0) => return None,
+            (1, 1) => format!(
+                "p.{}{}{}",
+                three_letter(deleted[0]),
+                prefix + 1,
+                three_letter(inserted[0])
+            ),
+            (length, 0) => format!(
+                "p.{}del",
+                protein_range(protein, prefix, prefix + length - 1)
+            ),
+            (0, length) => {
+                if prefix >= length && &protein[prefix - length..prefix] == inserted {
+                    format!(
+                        "p.{}dup",
+                        protein_range(protein, prefix - length, prefix - 1)
+                    )
+                } else if prefix >= 1 && prefix < protein.len() {
+                    format!(
+                        "p.{}ins{}",
+                        protein_range(protein, prefix - 1, prefix),
+                        three_letters(inserted)
+                    )
+                } else {
+                    return None;
+                }
+            }
+            (length, _) => format!(
+                "p.{}delins{}",
+                protein_range(protein, prefix, prefix + length - 1),
+                three_letters(inserted)
+            ),
+        })
+    }
+}
+
+impl variant2myth::Annotator for Hgvs<'_> {
+    fn annotate(
+        &self,
+        _variant: &variant::Variant,
+        _memoizor: &mut memoizor::Memoizor,
+    ) -> Vec<effect::Effect> {
+        vec![]
+    }
+
+    fn annotate_myth(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+        annotation_myth: &mut myth::AnnotationMythBuilder,
+    ) {
+        let transcript_id = memoizor
+            .transcript()
+            .map(|a| String::from_utf8_lossy(a.get_attribute().get_id()).to_string())
+            .unwrap_or_default();
+
+        // HGVS describe variant at the most 3' position
+        let mut right_aligned = variant.clone();
+        right_aligned.right_align(self.sequences);
+
+        let hgvs_g = self.genomic(&right_aligned).unwrap_or_default();
+
+        let transcript_variant = match memoizor.strand() {
+            annotation::Strand::Forward => right_aligned,
+            annotation::Strand::Reverse => {
+                let mut left_aligned = variant.clone();
+                left_aligned.left_align(self.sequences);
+                left_aligned
+            }
+        };
+
+        let hgvs_c = self
+            .transcript(&transcript_variant, memoizor)
+            .map(|description| format!("{}:{}", transcript_id, description))
+            .unwrap_or_default();
+
+        let hgvs_p = match memoizor.coding_sequences(variant) {
+            Ok(Some((coding, coding_var))) => self
+                .protein(&coding, &coding_var)
+                .map(|description| format!("{}:{}", transcript_id, description))
+                .unwrap_or_default(),
+            Ok(None) => String::new(),
+            Err(error) => {
+                log::error!("{:?}", error);
+                String::new()
+            }
+        };
+
+        annotation_myth.set_hgvs(
+            hgvs_c.into_bytes(),
+            hgvs_p.into_bytes(),
+            hgvs_g.into_bytes(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use super::*;
+    use crate::annotations_db;
+    use crate::error;
+    use crate::test_data;
+
+    #[test]
+    fn trim_variant() {
+        assert_eq!(trim(10, b"A", b"G"), (10, &b"A"[..], &b"G"[..]));
+        assert_eq!(trim(10, b"AT", b"A"), (11, &b"T"[..], &b""[..]));
+        assert_eq!(trim(10, b"A", b"ACC"), (11, &b""[..], &b"CC"[..]));
+        assert_eq!(trim(10, b"ACGT", b"AGGT"), (11, &b"C"[..], &b"G"[..]));
+    }
+
+    #[test]
+    fn position() {
+        let exons = vec![(10, 20), (30, 40)];
+
+        assert_eq!(locate(&exons, 5), (-4, 0));
+        assert_eq!(locate(&exons, 10), (1, 0));
+        assert_eq!(locate(&exons, 19), (10, 0));
+        assert_eq!(locate(&exons, 22), (10, 3));
+        assert_eq!(locate(&exons, 28), (11, -2));
+        assert_eq!(locate(&exons, 30), (11, 0));
+        assert_eq!(locate(&exons, 42), (23, 0));
+
+        assert_eq!(format_position(-4, 0, None, 20), "-5");
+        assert_eq!(format_position(10, 3, None, 20), "10+3");
+        assert_eq!(format_position(11, -2, None, 20), "11-2");
+        assert_eq!(format_position(23, 0, None, 20), "*3");
+
+        assert_eq!(format_position(2, 0, Some((5, 15)), 20), "-3");
+        assert_eq!(format_position(5, 0, Some((5, 15)), 20), "1");
+        assert_eq!(format_position(10, 3, Some((5, 15)), 20), "6+3");
+        assert_eq!(format_position(18, 0, Some((5, 15)), 20), "*3");
+    }
+
+    #[test]
+    fn genomic() -> error::Result<()> {
+        let translate = translate::Translate::default();
+        let hgvs = Hgvs::new(&translate, &test_data::SEQUENCE_DB);
+
+        let variant = variant::Variant::test_variant(b"chrA", 100, b"A", b"G", None)?;
+        assert_eq!(hgvs.genomic(&variant), Some("chrA:g.101A>G".to_string()));
+
+        let variant = variant::Variant::test_variant(b"chrA", 240, b"GT", b"G", None)?;
+        assert_eq!(hgvs.genomic(&variant), Some("chrA:g.242del".to_string()));
+
+        let variant = variant::Variant::test_variant(b"chrA", 246, b"T", b"TTTCAAT", None)?;
+        assert_eq!(
+            hgvs.genomic(&variant),
+            Some("chrA:g.242_247dup".to_string())
+        );
+
+        let variant = variant::Variant::test_variant(b"chrA", 246, b"T", b"TGG", None)?;
+        assert_eq!(
+            hgvs.genomic(&variant),
+            Some("chrA:g.247_248insGG".to_string())
+        );
+
+        let variant = variant::Variant::test_variant(b"chrA", 240, b"GTT", b"GA", None)?;
+        assert_eq!(
+            hgvs.genomic(&variant),
+            Some("chrA:g.242_243delinsA".to_string())
+        );
+
+        let variant =
+            variant::Variant::test_variant(b"chrA", 240, b"G", b"<DEL>", Some(b"SVLEN=10"))?;
+        assert_eq!(
+            hgvs.genomic(&variant),
+            Some("chrA:g.242_251del".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn transcript() -> error::Result<()> {
+        let translate = translate::Translate::default();
+        let hgvs = Hgvs::new(&translate, &test_data::SEQUENCE_DB);
+
+        let reader: std::io::BufReader<Box<dyn std::io::Read + std::marker::Send>> =
+            std::io::BufReader::new(Box::new(test_data::GFF));
+        let annotations_db = annotations_db::AnnotationsDataBase::from_reader(reader, 100)?;
+
+        let truth = [
+            (b"ENST00000797271.1", 100, &b"A"[..], &b"G"[..], "n.41A>G"),
+            (b"ENST00000797271.1", 55, b"A", b"G", "n.-5A>G"),
+            (b"ENST00000797271.1", 264, b"A", b"G", "n.201+4A>G"),
+            (b"ENST00000797271.1", 13199, b"A", b"G", "n.202-2A>G"),
+            (b"ENST00000797271.1", 240, b"GT", b"G", "n.182del"),
+            (b"ENST00000797271.1", 246, b"T", b"TTTCAAT", "n.182_187dup"),
+            (b"ENST00000837983.1", 121695598, b"G", b"A", "n.1C>T"),
+            (b"ENST00000837983.1", 121695270, b"G", b"A", "n.329C>T"),
+        ];
+
+        for (transcript_id, position, ref_seq, alt_seq, result) in truth {
+            let variant =
+                variant::Variant::test_variant(b"chrA", position, ref_seq, alt_seq, None)?;
+            let not_coding_annotation =
+                annotations_db.get_annotations(&variant.seqname, variant.get_interval());
+            let mut memoizor = memoizor::Memoizor::new(
+                transcript_id,
+                &annotations_db,
+                &test_data::SEQUENCE_DB,
+                &not_coding_annotation,
+            );
+
+            assert_eq!(
+                hgvs.transcript(&variant, &mut memoizor),
+                Some(result.to_string())
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn protein() {
+        let translate = translate::Translate::default();
+        let hgvs = Hgvs::new(&translate, &test_data::SEQUENCE_DB);
+
+        // M K F *
+        let coding = b"ATGAAATTTTAA";
+
+        let truth: [(&[u8], Option<&str>); 13] = [
+            (b"ATGAAATTTTAA", None),
+            (b"ATGAAGTTTTAA", Some("p.Lys2=")),
+            (b"ATGGAATTTTAA", Some("p.Lys2Glu")),
+            (b"ATGTAATTTTAA", Some("p.Lys2Ter")),
+            (b"ATGAAATTTTAC", Some("p.Ter4TyrextTer?")),
+            (b"ATGAAATTTTACGGGTAA", Some("p.Ter4TyrextTer3")),
+            (b"ACGAAATTTTAA", Some("p.Met1?")),
+            (b"ATGTTTTAA", Some("p.Lys2del")),
+            (b"ATGAAAAAATTTTAA", Some("p.Lys2dup")),
+            (b"ATGAAAGGGTTTTAA", Some("p.Lys2_Phe3insGly")),
+            (b"ATGGGGCCCTTTTAA", Some("p.Lys2delinsGlyPro")),
+            (b"ATGAATTTTAA", Some("p.Lys2AsnfsTer?")),
+            (b"ATGTAAATTTTAA", Some("p.Lys2Ter")),
+        ];
+
+        for (coding_var, result) in truth {
+            assert_eq!(
+                hgvs.protein(coding, coding_var),
+                result.map(|r| r.to_string())
+            );
+        }
+    }
+}
@@ -1,32 +1,129 @@
-//! An annotator for upstream variant
+//! An annotator that compare reference and variant coding sequence to classify coding effect
 
 /* std use */
 
 /* crate use */
 
 /* project use */
-use crate::annotation;
 use crate::effect;
 use crate::memoizor;
-use crate::sequences_db;
 use crate::translate;
 use crate::variant;
 use crate::variant2myth;
 
 pub struct SequenceAnalysis<'a> {
     translate: &'a translate::Translate,
-    sequences: &'a sequences_db::SequencesDataBase,
 }
 
 impl<'a> SequenceAnalysis<'a> {
-    pub const fn new(
-        translate: &'a translate::Translate,
-        sequences: &'a sequences_db::SequencesDataBase,
-    ) -> Self {
-        Self {
-            translate,
-            sequences,
+    pub const fn new(translate: &'a translate::Translate) -> Self {
+        Self { translate }
+    }
+}
+
+impl SequenceAnalysis<'_> {
+    /// Classify change between reference and edited coding sequence
+    fn classify(&self, coding: &[u8], coding_var: &[u8]) -> Vec<effect::Effect> {
+        let mut effects = Vec::new();
+
+        let first_diff = coding
+            .iter()
+            .zip(coding_var.iter())
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+        let first_codon = first_diff / 3;
+        let length_diff = coding_var.len() as i64 - coding.len() as i64;
+
+        // Start codon
+        if first_codon == 0 && coding.len() >= 3 && self.translate.is_start(&coding[..3]) {
+            if coding_var.len() >= 3 && self.translate.is_start(&coding_var[..3]) {
+                effects.push(effect::Effect::StartRetainedVariant);
+            } else {
+                effects.push(effect::Effect::StartLost);
+            }
+        }
+
+        if length_diff % 3 != 0 {
+            effects.push(effect::Effect::FrameshiftVariant);
+            return effects;
+        }
+
+        if !effects.is_empty() {
+            return effects;
+        }
+
+        let codon_diff = length_diff / 3;
+        let protein = self.translate.translate(coding);
+        let protein_var = self.translate.translate(coding_var);
+
+        let stop = protein.iter().position(|aa| *aa == b'*');
+        let stop_var = protein_var.iter().position(|aa| *aa == b'*');
+
+        // Stop codon
+        if let Some(stop) = stop {
+            if first_codon > stop {
+                // variant after stop codon
+                return effects;
+            }
+
+            let expected_stop = stop as i64 + codon_diff;
+            match stop_var.map(|s| s as i64) {
+                Some(s) if s < expected_stop => effects.push(effect::Effect::StopGained),
+                Some(s) if s == expected_stop && first_codon == stop => {
+                    effects.push(effect::Effect::StopRetainedVariant)
+                }
+                Some(s) if s == expected_stop => (),
+                _ => effects.push(effect::Effect::StopLost),
+            }
+        } else if stop_var.is_some() {
+            effects.push(effect::Effect::StopGained);
+        }
+
+        if !effects.is_empty() {
+            return effects;
         }
+
+        // Protein change
+        let protein = &protein[..stop.map(|s| s + 1).unwrap_or(protein.len())];
+        let protein_var = &protein_var[..stop_var.map(|s| s + 1).unwrap_or(protein_var.len())];
+
+        let prefix = protein
+            .iter()
+            .zip(protein_var.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = protein[prefix..]
+            .iter()
+            .rev()
+            .zip(protein_var[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        match codon_diff.cmp(&0) {
+            std::cmp::Ordering::Equal => {
+                if protein == protein_var {
+                    effects.push(effect::Effect::SynonymousVariant)
+                } else {
+                    effects.push(effect::Effect::MissenseVariant)
+                }
+            }
+            std::cmp::Ordering::Greater => {
+                if prefix + suffix == protein.len() {
+                    effects.push(effect::Effect::ConservativeInframeInsertion)
+                } else {
+                    effects.push(effect::Effect::DisruptiveInframeInsertion)
+                }
+            }
+            std::cmp::Ordering::Less => {
+                if prefix + suffix == protein_var.len() {
+                    effects.push(effect::Effect::ConservativeInframeDeletion)
+                } else {
+                    effects.push(effect::Effect::DisruptiveInframeDeletion)
+                }
+            }
+        }
+
+        effects
     }
 }
 
@@ -36,54 +133,14 @@ impl variant2myth::Annotator for SequenceAnalysis<'_> {
         variant: &variant::Variant,
         memoizor: &mut memoizor::Memoizor,
     ) -> Vec<effect::Effect> {
-        let start_position = memoizor
-            .coding_annotation()
-            .unwrap()
-            .iter()
-            .find(|&x| x.get_feature() == b"start_codon")
-            .map(|x| x.get_start());
-
-        let stop_position = memoizor
-            .coding_annotation()
-            .unwrap()
-            .iter()
-            .find(|&x| x.get_feature() == b"stop_codon")
-            .map(|x| x.get_stop());
-
-        let strand = memoizor
-            .transcript()
-            .map(|x| x.get_strand())
-            .cloned()
-            .unwrap_or(annotation::Strand::Forward);
-
-        let exon_annot = memoizor.exons_annotation();
-        let exon_annot_proxy = exon_annot.iter().collect::<Vec<&annotation::Annotation>>();
-
-        // No exon in associate annotation no sequence analysis
-        if exon_annot_proxy.is_empty() {
+        // Structural variant effect are compute by structural annotator
+        if variant.structural() {
             return vec![];
         }
 
-        let coding =
-            match self
-                .sequences
-                .coding(&exon_annot_proxy, strand, start_position, stop_position)
-            {
-                Ok(sequence) => sequence,
-                Err(error) => {
-                    log::error!("{:?}", error);
-                    return vec![];
-                }
-            };
-
-        let coding_var = match self.sequences.coding_edit(
-            &exon_annot_proxy,
-            strand,
-            variant,
-            start_position,
-            stop_position,
-        ) {
-            Ok(sequence) => sequence,
+        let (coding, coding_var) = match memoizor.coding_sequences(variant) {
+            Ok(Some(sequences)) => sequences,
+            Ok(None) => return vec![],
             Err(error) => {
                 log::error!("{:?}", error);
                 return vec![];
@@ -95,25 +152,89 @@ impl variant2myth::Annotator for SequenceAnalysis<'_> {
             return vec![];
         }
 
-        let translate = self.translate.translate(&coding);
-        let translate_var = self.translate.translate(&coding_var);
-
-        if translate != translate_var {
-            log::debug!("ORIGINAL: {}", String::from_utf8(translate).unwrap());
-            log::debug!("EDIT    : {}", String::from_utf8(translate_var).unwrap());
-            log::debug!("VARIANT : {}", variant);
-            log::debug!(
-                "ID      : {}",
-                String::from_utf8(
-                    memoizor.coding_annotation().unwrap()[0]
-                        .get_attribute()
-                        .get_id()
-                        .to_vec()
-                )
-                .unwrap()
-            );
-        }
+        log::trace!("VARIANT : {}", variant);
+        log::trace!("ORIGINAL: {}", String::from_utf8_lossy(&coding));
+        log::trace!("EDIT    : {}", String::from_utf8_lossy(&coding_var));
+
+        self.classify(&coding, &coding_var)
+    }
+}
 
-        vec![]
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use crate::effect;
+    use crate::translate;
+
+    use super::SequenceAnalysis;
+
+    #[test]
+    fn classify() {
+        let translate = translate::Translate::default();
+        let obj = SequenceAnalysis::new(&translate);
+
+        // M K F *
+        let coding = b"ATGAAATTTTAA";
+
+        assert_eq!(
+            obj.classify(coding, b"ATGAAGTTTTAA"),
+            vec![effect::Effect::SynonymousVariant]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGGAATTTTAA"),
+            vec![effect::Effect::MissenseVariant]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGTAATTTTAA"),
+            vec![effect::Effect::StopGained]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAAATTTTAC"),
+            vec![effect::Effect::StopLost]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAAATTTTAG"),
+            vec![effect::Effect::StopRetainedVariant]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ACGAAATTTTAA"),
+            vec![effect::Effect::StartLost]
+        );
+        assert_eq!(
+            obj.classify(coding, b"CTGAAATTTTAA"),
+            vec![effect::Effect::StartRetainedVariant]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAAAATTTTAA"),
+            vec![effect::Effect::FrameshiftVariant]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAAAGGGTTTTAA"),
+            vec![effect::Effect::ConservativeInframeInsertion]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAACCCATTTTAA"),
+            vec![effect::Effect::DisruptiveInframeInsertion]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAAATAATTTTAA"),
+            vec![effect::Effect::StopGained]
+        );
+
+        // M K F G *
+        let coding = b"ATGAAATTTGGGTAA";
+
+        assert_eq!(
+            obj.classify(coding, b"ATGAAAGGGTAA"),
+            vec![effect::Effect::ConservativeInframeDeletion]
+        );
+        assert_eq!(
+            obj.classify(coding, b"ATGAATGGGTAA"),
+            vec![effect::Effect::DisruptiveInframeDeletion]
+        );
     }
 }
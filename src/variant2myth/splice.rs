@@ -0,0 +1,200 @@
+//! An annotator for splice site and splice region variant
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::annotation;
+use crate::effect;
+use crate::memoizor;
+use crate::variant;
+use crate::variant2myth;
+
+/// Size of windows around exon boundaries
+#[derive(std::clone::Clone, std::marker::Copy, std::fmt::Debug, PartialEq)]
+pub struct SpliceWindow {
+    /// Number of intronic bases of donor and acceptor site
+    pub site: u64,
+    /// Number of exonic bases of splice region
+    pub region_exon: u64,
+    /// Last intronic base of splice region
+    pub region_intron: u64,
+}
+
+impl std::default::Default for SpliceWindow {
+    fn default() -> Self {
+        Self {
+            site: 2,
+            region_exon: 3,
+            region_intron: 8,
+        }
+    }
+}
+
+pub struct Splice {
+    window: SpliceWindow,
+}
+
+impl Splice {
+    pub fn new(window: SpliceWindow) -> Self {
+        Self { window }
+    }
+}
+
+impl variant2myth::Annotator for Splice {
+    fn annotate(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+    ) -> Vec<effect::Effect> {
+        let strand = memoizor.strand();
+        let interval = variant.get_interval();
+        let window = &self.window;
+
+        let mut donor = false;
+        let mut acceptor = false;
+        let mut region = false;
+
+        // exons are sorted by genomic position, intron is between two consecutive exons
+        for exons in memoizor.exons_annotation().windows(2) {
+            let intron = exons[0].get_stop()..exons[1].get_start();
+            if intron.is_empty() {
+                continue;
+            }
+
+            let site = window.site.min(intron.end - intron.start);
+            let left_site = intron.start..intron.start + site;
+            let right_site = intron.end - site..intron.end;
+
+            let (left, right) = match strand {
+                annotation::Strand::Forward => (&mut donor, &mut acceptor),
+                annotation::Strand::Reverse => (&mut acceptor, &mut donor),
+            };
+            *left |= variant2myth::overlap(&interval, &left_site);
+            *right |= variant2myth::overlap(&interval, &right_site);
+
+            let intron_region = window.region_intron.min(intron.end - intron.start);
+            region |= [
+                intron.start.saturating_sub(window.region_exon)..intron.start,
+                left_site.end..intron.start + intron_region,
+                intron.end - intron_region..right_site.start,
+                intron.end..intron.end + window.region_exon,
+            ]
+            .iter()
+            .any(|r| variant2myth::overlap(&interval, r));
+        }
+
+        let mut effects = vec![];
+        if donor {
+            effects.push(effect::Effect::SpliceDonorVariant);
+        }
+        if acceptor {
+            effects.push(effect::Effect::SpliceAcceptorVariant);
+        }
+        if region {
+            effects.push(effect::Effect::SpliceRegionVariant);
+        }
+
+        effects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use crate::effect;
+    use crate::error;
+    use crate::memoizor;
+    use crate::test_data;
+    use crate::variant;
+    use crate::variant2myth::Annotator as _;
+
+    use super::*;
+
+    #[test]
+    fn splice() -> error::Result<()> {
+        let annotations_db = &*test_data::TRANSCRIPTS_DB;
+
+        let obj = Splice::new(SpliceWindow::default());
+
+        // Forward transcript, intron is 100..200 (0-based)
+        let truth: Vec<(u64, Vec<effect::Effect>)> = vec![
+            (90, vec![]),
+            (97, vec![effect::Effect::SpliceRegionVariant]),
+            (100, vec![effect::Effect::SpliceDonorVariant]),
+            (101, vec![effect::Effect::SpliceDonorVariant]),
+            (102, vec![effect::Effect::SpliceRegionVariant]),
+            (107, vec![effect::Effect::SpliceRegionVariant]),
+            (108, vec![]),
+            (191, vec![]),
+            (192, vec![effect::Effect::SpliceRegionVariant]),
+            (198, vec![effect::Effect::SpliceAcceptorVariant]),
+            (200, vec![effect::Effect::SpliceRegionVariant]),
+            (203, vec![]),
+        ];
+        for (position, effects) in truth {
+            let variant = variant::Variant::test_variant(b"chrA", position, b"A", b"C", None)?;
+            let mut memoizor =
+                memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+            assert_eq!(
+                obj.annotate(&variant, &mut memoizor),
+                effects,
+                "position {}",
+                position
+            );
+        }
+
+        // Reverse transcript, introns are 100..150 and 200..250 (0-based)
+        let truth: Vec<(u64, Vec<effect::Effect>)> = vec![
+            (100, vec![effect::Effect::SpliceAcceptorVariant]),
+            (149, vec![effect::Effect::SpliceDonorVariant]),
+            (200, vec![effect::Effect::SpliceAcceptorVariant]),
+            (249, vec![effect::Effect::SpliceDonorVariant]),
+        ];
+        for (position, effects) in truth {
+            let variant = variant::Variant::test_variant(b"chrB", position, b"A", b"C", None)?;
+            let mut memoizor =
+                memoizor::Memoizor::new(b"tr5", annotations_db, &test_data::SEQUENCE_DB, &[]);
+            assert_eq!(obj.annotate(&variant, &mut memoizor), effects);
+        }
+
+        // Deletion that cover donor site and splice region
+        let variant = variant::Variant::test_variant(b"chrA", 99, b"AAAA", b"A", None)?;
+        let mut memoizor =
+            memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+        assert_eq!(
+            obj.annotate(&variant, &mut memoizor),
+            vec![
+                effect::Effect::SpliceDonorVariant,
+                effect::Effect::SpliceRegionVariant
+            ]
+        );
+
+        // Smaller windows
+        let obj = Splice::new(SpliceWindow {
+            site: 1,
+            region_exon: 1,
+            region_intron: 4,
+        });
+        let truth: Vec<(u64, Vec<effect::Effect>)> = vec![
+            (98, vec![]),
+            (99, vec![effect::Effect::SpliceRegionVariant]),
+            (100, vec![effect::Effect::SpliceDonorVariant]),
+            (101, vec![effect::Effect::SpliceRegionVariant]),
+            (104, vec![]),
+        ];
+        for (position, effects) in truth {
+            let variant = variant::Variant::test_variant(b"chrA", position, b"A", b"C", None)?;
+            let mut memoizor =
+                memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+            assert_eq!(obj.annotate(&variant, &mut memoizor), effects);
+        }
+
+        Ok(())
+    }
+}
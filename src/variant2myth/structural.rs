@@ -0,0 +1,264 @@
+//! An annotator for structural variant effect on transcript and exons
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::annotation;
+use crate::effect;
+use crate::memoizor;
+use crate::myth;
+use crate::variant;
+use crate::variant2myth;
+
+pub struct Structural;
+
+impl Structural {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+fn cover(a: &core::ops::Range<u64>, b: &core::ops::Range<u64>) -> bool {
+    a.start <= b.start && b.end <= a.end
+}
+
+/// Reference interval changed by variant, copy of a tandem duplication is insert after duplicated segment so only this segment is changed
+fn changed_interval(variant: &variant::Variant) -> core::ops::Range<u64> {
+    let interval = variant.get_interval();
+    match variant.variant_type {
+        variant::Type::Dup(size) => interval.start..interval.end - size,
+        _ => interval,
+    }
+}
+
+/// Rank, in transcript orientation, of first and last exon overlapped by variant and number of exons
+fn affected_exons(
+    variant: &variant::Variant,
+    memoizor: &mut memoizor::Memoizor,
+) -> Option<(usize, usize, usize)> {
+    let interval = changed_interval(variant);
+    let strand = memoizor.strand();
+    let exons = memoizor.exons_annotation();
+    let total = exons.len();
+
+    // exons are sorted by genomic position
+    let first = exons
+        .iter()
+        .position(|exon| variant2myth::overlap(&interval, &exon.get_interval()))?;
+    let last = exons
+        .iter()
+        .rposition(|exon| variant2myth::overlap(&interval, &exon.get_interval()))?;
+
+    match strand {
+        annotation::Strand::Forward => Some((first + 1, last + 1, total)),
+        annotation::Strand::Reverse => Some((total - last, total - first, total)),
+    }
+}
+
+impl variant2myth::Annotator for Structural {
+    fn annotate(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+    ) -> Vec<effect::Effect> {
+        if !variant.structural() {
+            return vec![];
+        }
+
+        let interval = changed_interval(variant);
+        let transcript = match memoizor.transcript() {
+            Some(transcript) if variant2myth::overlap(&interval, &transcript.get_interval()) => {
+                transcript.get_interval()
+            }
+            _ => return vec![],
+        };
+        let whole_transcript = cover(&interval, &transcript);
+        let whole_gene = memoizor
+            .gene()
+            .is_some_and(|gene| cover(&interval, &gene.get_interval()));
+        let exonic = affected_exons(variant, memoizor).is_some();
+
+        let mut effects = vec![];
+        match variant.variant_type {
+            variant::Type::Del(_) => {
+                if whole_gene {
+                    effects.push(effect::Effect::FeatureAblation);
+                }
+                if whole_transcript {
+                    effects.push(effect::Effect::TranscriptAblation);
+                } else if exonic {
+                    effects.push(effect::Effect::ExonLossVariant);
+                }
+            }
+            variant::Type::Dup(_) => {
+                if whole_transcript {
+                    effects.push(effect::Effect::Duplication);
+                } else if exonic {
+                    effects.push(effect::Effect::Duplication);
+                    effects.push(effect::Effect::FeatureElongation);
+                }
+            }
+            variant::Type::Ins(_) => {
+                if exonic {
+                    effects.push(effect::Effect::FeatureElongation);
+                }
+            }
+            variant::Type::Inv(_) => {
+                if whole_transcript || exonic {
+                    effects.push(effect::Effect::Inversion);
+                }
+            }
+            // Copy number change direction is unknown, only affected exons are report
+            variant::Type::Cnv(_) | variant::Type::Small => {}
+            // Junction effect are compute by fusion annotator
+            variant::Type::Bnd(_) => {}
+        }
+
+        effects
+    }
+
+    fn annotate_myth(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+        annotation_myth: &mut myth::AnnotationMythBuilder,
+    ) {
+        if variant.structural() {
+            match affected_exons(variant, memoizor) {
+                Some((first, last, total)) if first == last => {
+                    annotation_myth.set_exon_rank(format!("{}/{}", first, total).into_bytes())
+                }
+                Some((first, last, total)) => annotation_myth
+                    .set_exon_rank(format!("{}-{}/{}", first, last, total).into_bytes()),
+                None => (),
+            }
+        }
+
+        annotation_myth.extend_effect(&self.annotate(variant, memoizor));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use crate::effect;
+    use crate::error;
+    use crate::memoizor;
+    use crate::myth;
+    use crate::test_data;
+    use crate::variant;
+    use crate::variant2myth::Annotator as _;
+
+    use super::*;
+
+    #[test]
+    fn structural() -> error::Result<()> {
+        let annotations_db = &*test_data::TRANSCRIPTS_DB;
+
+        let obj = Structural::new();
+
+        let check = |transcript: &[u8],
+                     variant: variant::Variant,
+                     effects: Vec<effect::Effect>,
+                     rank: &[u8]|
+         -> error::Result<()> {
+            let mut memoizor =
+                memoizor::Memoizor::new(transcript, annotations_db, &test_data::SEQUENCE_DB, &[]);
+            let mut annotation_myth =
+                myth::AnnotationMyth::from_annotation(memoizor.transcript().unwrap())
+                    .effects(vec![]);
+
+            obj.annotate_myth(&variant, &mut memoizor, &mut annotation_myth);
+            let annotation = annotation_myth.build()?;
+
+            assert_eq!(annotation.effects, effects, "{}", variant);
+            assert_eq!(annotation.exon_rank, rank, "{}", variant);
+
+            Ok(())
+        };
+
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 10, b"A", b"<DEL>", Some(b"SVLEN=400"))?,
+            vec![
+                effect::Effect::FeatureAblation,
+                effect::Effect::TranscriptAblation,
+            ],
+            b"1-3/3",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 140, b"A", b"<DEL>", Some(b"SVLEN=80"))?,
+            vec![effect::Effect::ExonLossVariant],
+            b"2/3",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 110, b"A", b"<DEL>", Some(b"SVLEN=20"))?,
+            vec![],
+            b"",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 90, b"A", b"<DUP>", Some(b"SVLEN=40"))?,
+            vec![
+                effect::Effect::Duplication,
+                effect::Effect::FeatureElongation,
+            ],
+            b"1/3",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 90, b"A", b"<DUP>", Some(b"SVLEN=70"))?,
+            vec![
+                effect::Effect::Duplication,
+                effect::Effect::FeatureElongation,
+            ],
+            b"1-2/3",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 160, b"A", b"<INS>", Some(b"SVLEN=100"))?,
+            vec![effect::Effect::FeatureElongation],
+            b"2/3",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 120, b"A", b"<INS>", Some(b"SVLEN=100"))?,
+            vec![],
+            b"",
+        )?;
+        check(
+            b"tr5",
+            variant::Variant::test_variant(b"chrB", 160, b"A", b"<INV>", Some(b"SVLEN=200"))?,
+            vec![effect::Effect::Inversion],
+            b"1-2/3",
+        )?;
+        check(
+            b"tr5",
+            variant::Variant::test_variant(b"chrB", 160, b"A", b"<CNV>", Some(b"SVLEN=10"))?,
+            vec![],
+            b"2/3",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 160, b"A", b"C", Some(b"."))?,
+            vec![],
+            b"",
+        )?;
+        check(
+            b"tr4",
+            variant::Variant::test_variant(b"chrB", 400, b"A", b"<DEL>", Some(b"SVLEN=100"))?,
+            vec![],
+            b"",
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+//! An annotator for variant position relative to exons and introns of transcript
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::effect;
+use crate::memoizor;
+use crate::variant;
+use crate::variant2myth;
+
+pub struct TranscriptStructure;
+
+impl TranscriptStructure {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl variant2myth::Annotator for TranscriptStructure {
+    fn annotate(
+        &self,
+        variant: &variant::Variant,
+        memoizor: &mut memoizor::Memoizor,
+    ) -> Vec<effect::Effect> {
+        let interval = variant.get_interval();
+
+        // Variant is upstream or downstream of transcript
+        match memoizor.transcript() {
+            Some(transcript) if variant2myth::overlap(&interval, &transcript.get_interval()) => (),
+            _ => return vec![],
+        }
+
+        let coding = memoizor.coding_interval().is_some();
+
+        let exons = memoizor.exons_annotation();
+        let exonic = exons
+            .iter()
+            .any(|exon| variant2myth::overlap(&interval, &exon.get_interval()));
+        let intronic = exons
+            .windows(2)
+            .map(|exons| exons[0].get_stop()..exons[1].get_start())
+            .any(|intron| variant2myth::overlap(&interval, &intron));
+
+        let mut effects = vec![];
+        match (coding, exonic) {
+            (true, true) => effects.push(effect::Effect::ExonRegion),
+            (false, true) => effects.push(effect::Effect::NonCodingTranscriptExonVariant),
+            (_, false) => {}
+        }
+        if intronic {
+            effects.push(effect::Effect::IntronVariant);
+        }
+        if !coding && !exonic {
+            effects.push(effect::Effect::NonCodingTranscriptVariant);
+        }
+
+        effects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* std use */
+
+    /* crate use */
+
+    /* project use */
+    use crate::effect;
+    use crate::error;
+    use crate::memoizor;
+    use crate::test_data;
+    use crate::variant;
+    use crate::variant2myth::Annotator as _;
+
+    use super::*;
+
+    #[test]
+    fn transcript_structure() -> error::Result<()> {
+        let annotations_db = &*test_data::TRANSCRIPTS_DB;
+
+        let obj = TranscriptStructure::new();
+
+        let truth: Vec<(&[u8], u64, Vec<effect::Effect>)> = vec![
+            (b"tr1", 10, vec![]),
+            (b"tr1", 70, vec![effect::Effect::ExonRegion]),
+            (b"tr1", 150, vec![effect::Effect::IntronVariant]),
+            (
+                b"tr2",
+                70,
+                vec![effect::Effect::NonCodingTranscriptExonVariant],
+            ),
+            (
+                b"tr2",
+                150,
+                vec![
+                    effect::Effect::IntronVariant,
+                    effect::Effect::NonCodingTranscriptVariant,
+                ],
+            ),
+            (b"tr2", 350, vec![]),
+        ];
+
+        for (transcript, position, effects) in truth {
+            let variant = variant::Variant::test_variant(b"chrA", position, b"A", b"C", None)?;
+            let mut memoizor =
+                memoizor::Memoizor::new(transcript, annotations_db, &test_data::SEQUENCE_DB, &[]);
+
+            assert_eq!(obj.annotate(&variant, &mut memoizor), effects);
+        }
+
+        // Deletion overlap exon and intron
+        let variant = variant::Variant::test_variant(b"chrA", 98, b"AAAA", b"A", None)?;
+        let mut memoizor =
+            memoizor::Memoizor::new(b"tr1", annotations_db, &test_data::SEQUENCE_DB, &[]);
+        assert_eq!(
+            obj.annotate(&variant, &mut memoizor),
+            vec![effect::Effect::ExonRegion, effect::Effect::IntronVariant]
+        );
+
+        Ok(())
+    }
+}
@@ -337,6 +337,8 @@ fn logging_updown_setup() -> anyhow::Result<()> {
         "tests/data/references.fasta",
         "-a",
         "tests/data/annotations.gff3",
+        "--ref-mismatch",
+        "flag",
         "parquet",
         "-p",
         output_path.to_str().unwrap(),
@@ -363,8 +365,10 @@ INFO Start read annotations
 INFO End read annotations
 INFO Start read translation table
 INFO End read translation table
-INFO Start annotate variant
-INFO End annotate variant
+INFO Start annotate variant tests/data/variants.vcf
+INFO End annotate variant tests/data/variants.vcf, 114 variants
+INFO Annotate 114 variants from 1 files
+WARN 98 variants with REF that didn't match reference genome
 "[..],
             );
         }
@@ -372,3 +376,591 @@ INFO End annotate variant
 
     Ok(())
 }
+
+#[cfg(feature = "vcf")]
+#[test]
+fn output_vcf() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let output_path = tmp_path.join("myth.vcf");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+    let args = vec![
+        "-i",
+        "tests/data/variants.vcf",
+        "-r",
+        "tests/data/references.fasta",
+        "-a",
+        "tests/data/annotations.gff3",
+        "-c",
+        "gene",
+        "vcf",
+        "-p",
+        output_path.to_str().unwrap(),
+    ];
+
+    cmd.args(&args);
+
+    if let Err(e) = cmd.assert().try_success() {
+        eprintln!(
+            "failled\n\targument: {}\n\toutput path {}",
+            args.join(" "),
+            output_path.to_str().unwrap()
+        );
+        return Err(e.into());
+    }
+
+    let input = std::fs::read_to_string("tests/data/variants.vcf")?;
+    let output = std::fs::read_to_string(&output_path)?;
+
+    let input_header = input
+        .lines()
+        .filter(|l| l.starts_with('#'))
+        .collect::<Vec<&str>>();
+    let output_header = output
+        .lines()
+        .filter(|l| l.starts_with('#') && !l.starts_with("##INFO=<ID=ANN,"))
+        .collect::<Vec<&str>>();
+    assert_eq!(input_header, output_header);
+
+    let mut input_records = input
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| l.split('\t').take(7).collect::<Vec<&str>>())
+        .collect::<Vec<Vec<&str>>>();
+    let mut output_records = output
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| l.split('\t').take(7).collect::<Vec<&str>>())
+        .collect::<Vec<Vec<&str>>>();
+    input_records.sort();
+    output_records.sort();
+    assert_eq!(input_records, output_records);
+
+    assert!(output
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .any(|l| l.contains("ANN=")));
+
+    Ok(())
+}
+
+#[test]
+fn cache() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let cache_path = tmp_path.join("variant_myth.cache");
+    let mut truth_path = std::path::PathBuf::from("tests/data/truth/annotator_feature");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+    cmd.args([
+        "-r",
+        "tests/data/references.fasta",
+        "-a",
+        "tests/data/annotations.gff3",
+        "index",
+        "-p",
+        cache_path.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let mut args = vec![
+        "-i",
+        "tests/data/variants.vcf",
+        "-r",
+        "tests/data/references.fasta",
+        "-a",
+        "tests/data/annotations.gff3",
+        "--cache",
+        cache_path.to_str().unwrap(),
+        "-c",
+        "feature",
+    ];
+
+    let mut output_path = tmp_path.join("myth");
+    if cfg!(feature = "json") {
+        output_path.set_extension("json");
+        truth_path.set_extension("json");
+        args.extend(["json", "-p", output_path.to_str().unwrap(), "-f", "nd-json"]);
+    } else {
+        output_path.set_extension("parquet");
+        truth_path.set_extension("parquet");
+        args.extend(["parquet", "-p", output_path.to_str().unwrap()]);
+    };
+
+    let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+    cmd.args(&args);
+    cmd.assert().success();
+
+    compare_by_record(truth_path, &output_path)?;
+
+    // Cache build with other updown distance is stale
+    let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+    cmd.args(["-d", "10"]).args(&args);
+    let output = cmd.output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cache is stale"));
+
+    Ok(())
+}
+
+#[test]
+fn indexed_reference() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let reference_path = tmp_path.join("references.fasta");
+    let mut truth_path = std::path::PathBuf::from("tests/data/truth/annotator_feature");
+
+    std::fs::copy("tests/data/references.fasta", &reference_path)?;
+
+    let mut args = vec![
+        "-i",
+        "tests/data/variants.vcf",
+        "-r",
+        reference_path.to_str().unwrap(),
+        "--indexed-reference",
+        "-a",
+        "tests/data/annotations.gff3",
+        "-c",
+        "feature",
+    ];
+
+    let mut output_path = tmp_path.join("myth");
+    if cfg!(feature = "json") {
+        output_path.set_extension("json");
+        truth_path.set_extension("json");
+        args.extend(["json", "-p", output_path.to_str().unwrap(), "-f", "nd-json"]);
+    } else {
+        output_path.set_extension("parquet");
+        truth_path.set_extension("parquet");
+        args.extend(["parquet", "-p", output_path.to_str().unwrap()]);
+    };
+
+    // Fasta index is missing
+    let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+    cmd.args(&args);
+    cmd.assert().failure();
+
+    // Each sequence of test reference is on one line
+    let mut fai = String::new();
+    let mut offset = 0;
+    let mut name = "";
+    for line in std::fs::read_to_string(&reference_path)?.split_inclusive('\n') {
+        offset += line.len();
+        if let Some(header) = line.strip_prefix('>') {
+            name = header.trim_end();
+        } else {
+            let length = line.trim_end().len();
+            fai.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                name,
+                length,
+                offset - line.len(),
+                length,
+                line.len()
+            ));
+        }
+    }
+    std::fs::write(tmp_path.join("references.fasta.fai"), fai)?;
+
+    let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+    cmd.args(&args);
+    cmd.assert().success();
+
+    compare_by_record(truth_path, &output_path)?;
+
+    Ok(())
+}
+
+#[test]
+fn stream() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let sorted_path = tmp_path.join("sorted.vcf");
+
+    // Group variants by chromosome
+    let vcf = std::fs::read_to_string("tests/data/variants.vcf")?;
+    let (header, mut records): (Vec<&str>, Vec<&str>) =
+        vcf.lines().partition(|line| line.starts_with('#'));
+    records.sort_by_key(|line| line.split('\t').next().unwrap_or(""));
+    std::fs::write(
+        &sorted_path,
+        header
+            .into_iter()
+            .chain(records)
+            .map(|line| format!("{}\n", line))
+            .collect::<String>(),
+    )?;
+
+    let run = |input: &std::path::Path, output: &std::path::Path, stream: bool| {
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth").unwrap();
+        cmd.args([
+            "-i",
+            input.to_str().unwrap(),
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+            "-c",
+            "feature",
+        ]);
+        if stream {
+            cmd.arg("--stream");
+        }
+        if cfg!(feature = "json") {
+            cmd.args(["json", "-p", output.to_str().unwrap(), "-f", "nd-json"]);
+        } else {
+            cmd.args(["parquet", "-p", output.to_str().unwrap()]);
+        }
+
+        cmd.output().unwrap()
+    };
+
+    let extension = if cfg!(feature = "json") {
+        "json"
+    } else {
+        "parquet"
+    };
+    let truth_path = tmp_path.join("truth").with_extension(extension);
+    let output_path = tmp_path.join("myth").with_extension(extension);
+
+    assert!(run(&sorted_path, &truth_path, false).status.success());
+    assert!(run(&sorted_path, &output_path, true).status.success());
+
+    compare_by_record(truth_path, &output_path)?;
+
+    // Variants of test file aren't group by chromosome
+    let output = run(
+        std::path::Path::new("tests/data/variants.vcf"),
+        &output_path,
+        true,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("aren't contiguous"));
+
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_order() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+
+    let run = |threads: &str| -> anyhow::Result<Vec<u8>> {
+        let output_path = tmp_path.join(format!("myth_{}", threads));
+
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth")?;
+        cmd.args([
+            "-i",
+            "tests/data/variants.vcf",
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+            "--threads",
+            threads,
+        ]);
+        if cfg!(feature = "json") {
+            cmd.args(["json", "-p", output_path.to_str().unwrap(), "-f", "nd-json"]);
+        } else {
+            cmd.args(["parquet", "-p", output_path.to_str().unwrap()]);
+        }
+        cmd.assert().success();
+
+        Ok(std::fs::read(output_path)?)
+    };
+
+    // One thread annotate variants in input order
+    assert_eq!(run("1")?, run("4")?);
+
+    Ok(())
+}
+
+#[test]
+fn multiple_inputs() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let subset_path = tmp_path.join("chrA.vcf");
+
+    let vcf = std::fs::read_to_string("tests/data/variants.vcf")?;
+    let lines: Vec<&str> = vcf
+        .lines()
+        .filter(|line| line.starts_with('#') || line.starts_with("chrA\t"))
+        .collect();
+    std::fs::write(&subset_path, lines.join("\n") + "\n")?;
+
+    let inputs = ["tests/data/variants.vcf", subset_path.to_str().unwrap()];
+
+    let run = |inputs: &[&str], outputs: &[std::path::PathBuf]| {
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth").unwrap();
+        cmd.args([
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+        ]);
+        for input in inputs {
+            cmd.args(["-i", input]);
+        }
+        if cfg!(feature = "json") {
+            cmd.args(["json", "-f", "nd-json"]);
+        } else {
+            cmd.arg("parquet");
+        }
+        for output in outputs {
+            cmd.args(["-p", output.to_str().unwrap()]);
+        }
+
+        cmd.assert()
+    };
+
+    // Each input is write in output with same index
+    let outputs = [tmp_path.join("all_0"), tmp_path.join("all_1")];
+    run(&inputs, &outputs).success();
+    for (index, input) in inputs.iter().enumerate() {
+        let single = tmp_path.join(format!("single_{}", index));
+        run(&[input], std::slice::from_ref(&single)).success();
+
+        assert_eq!(std::fs::read(&outputs[index])?, std::fs::read(single)?);
+    }
+
+    // Number of inputs and outputs must match
+    let assert = run(&inputs, &[tmp_path.join("mismatch")]).failure();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone())?;
+    assert!(stderr.contains("2 variant files but 1 output files"));
+
+    Ok(())
+}
+
+#[test]
+fn splice_window() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+
+    let run = |window: &[&str]| {
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth").unwrap();
+        cmd.args([
+            "-i",
+            "tests/data/variants.vcf",
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+        ]);
+        cmd.args(window);
+        cmd.args(["parquet", "-p", tmp_path.join("myth").to_str().unwrap()]);
+
+        cmd.assert()
+    };
+
+    run(&["--splice-site", "4", "--splice-region-intron", "4"]).success();
+
+    // Splice region must contains splice site
+    let assert = run(&["--splice-site", "4", "--splice-region-intron", "3"]).failure();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone())?;
+    assert!(stderr.contains("--splice-region-intron 3 must be greater or equal to --splice-site 4"));
+
+    // Splice region contains at least one exonic base
+    run(&["--splice-region-exon", "0"]).failure();
+
+    Ok(())
+}
+
+#[cfg(feature = "vcf")]
+#[test]
+fn stdio() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let output_path = tmp_path.join("myth.vcf");
+
+    let cmd = |input: &str, output: &str, format: &str| {
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth").unwrap();
+        cmd.args([
+            "-i",
+            input,
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+            format,
+            "-p",
+            output,
+        ]);
+        cmd
+    };
+
+    cmd(
+        "tests/data/variants.vcf",
+        output_path.to_str().unwrap(),
+        "vcf",
+    )
+    .assert()
+    .success();
+
+    // Read stdin and write stdout
+    let assert = cmd("-", "-", "vcf")
+        .pipe_stdin("tests/data/variants.vcf")?
+        .assert()
+        .success();
+    assert_eq!(assert.get_output().stdout, std::fs::read(&output_path)?);
+
+    // Parquet can't be write in stdout
+    #[cfg(feature = "parquet")]
+    {
+        let assert = cmd("tests/data/variants.vcf", "-", "parquet")
+            .assert()
+            .failure();
+        let stderr = String::from_utf8(assert.get_output().stderr.clone())?;
+        assert!(stderr.contains("Parquet output can't be write in stdout"));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "vcf")]
+#[test]
+fn bcf_and_region() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let subset_path = tmp_path.join("subset.vcf");
+    let bed_path = tmp_path.join("regions.bed");
+
+    let run = |input: &str, args: &[&str], name: &str| {
+        let output_path = tmp_path.join(name);
+
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth").unwrap();
+        cmd.args([
+            "-i",
+            input,
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+        ])
+        .args(args)
+        .args(["vcf", "-p", output_path.to_str().unwrap()]);
+
+        (cmd.assert(), output_path)
+    };
+
+    // Bcf and bgzip vcf give same result than vcf
+    let (assert, truth_path) = run("tests/data/sorted.vcf", &[], "vcf");
+    assert.success();
+    for input in ["tests/data/sorted.vcf.gz", "tests/data/sorted.bcf"] {
+        let (assert, output_path) = run(input, &[], "all");
+        assert.success();
+        assert_eq!(std::fs::read(&truth_path)?, std::fs::read(output_path)?);
+    }
+
+    // Records that overlap chrA:80001-90000 or chrB:1000-50000
+    let vcf = std::fs::read_to_string("tests/data/sorted.vcf")?;
+    let lines: Vec<&str> = vcf
+        .lines()
+        .filter(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if line.starts_with('#') {
+                return true;
+            }
+
+            let start = fields[1].parse::<u64>().unwrap();
+            let end = start + fields[3].len() as u64 - 1;
+            match fields[0] {
+                "chrA" => end >= 80001 && start <= 90000,
+                "chrB" => end >= 1000 && start <= 50000,
+                _ => false,
+            }
+        })
+        .collect();
+    std::fs::write(&subset_path, lines.join("\n") + "\n")?;
+    std::fs::write(&bed_path, "chrB\t999\t50000\nchrA\t80000\t90000\n")?;
+
+    let (assert, truth_path) = run(subset_path.to_str().unwrap(), &[], "subset");
+    assert.success();
+
+    let (assert, output_path) = run(
+        "tests/data/sorted.vcf.gz",
+        &[
+            "--region",
+            "chrB:1,000-50,000",
+            "--region",
+            "chrA:80001-90000",
+        ],
+        "region",
+    );
+    assert.success();
+    assert_eq!(std::fs::read(&truth_path)?, std::fs::read(output_path)?);
+
+    let (assert, output_path) = run(
+        "tests/data/sorted.bcf",
+        &["--regions-file", bed_path.to_str().unwrap()],
+        "regions_file",
+    );
+    assert.success();
+    assert_eq!(std::fs::read(&truth_path)?, std::fs::read(output_path)?);
+
+    // Region query need an index
+    let (assert, _) = run("tests/data/variants.vcf", &["--region", "chrA"], "no_index");
+    let stderr = String::from_utf8(assert.failure().get_output().stderr.clone())?;
+    assert!(stderr.contains("Region query need index tests/data/variants.vcf.tbi"));
+
+    Ok(())
+}
+
+#[test]
+fn bad_record() -> anyhow::Result<()> {
+    let tmp_path = tempfile::tempdir()?.into_path();
+    let input_path = tmp_path.join("bad.vcf");
+    let output_path = tmp_path.join("myth");
+
+    // First record can't be parse
+    let vcf = std::fs::read_to_string("tests/data/variants.vcf")?;
+    let (header, records): (Vec<&str>, Vec<&str>) =
+        vcf.lines().partition(|line| line.starts_with('#'));
+    let mut lines = header.clone();
+    lines.push("chrA\tten\t.\tA\tT");
+    lines.extend(records.iter().filter(|line| line.starts_with("chrA\t")));
+    std::fs::write(&input_path, lines.join("\n") + "\n")?;
+
+    let run = |args: &[&str]| {
+        let mut cmd = assert_cmd::Command::cargo_bin("variant_myth").unwrap();
+        cmd.args([
+            "-i",
+            input_path.to_str().unwrap(),
+            "-r",
+            "tests/data/references.fasta",
+            "-a",
+            "tests/data/annotations.gff3",
+            "-c",
+            "gene",
+            "-v",
+        ])
+        .args(args);
+        if cfg!(feature = "json") {
+            cmd.args(["json", "-p", output_path.to_str().unwrap(), "-f", "nd-json"]);
+        } else {
+            cmd.args(["parquet", "-p", output_path.to_str().unwrap()]);
+        }
+
+        cmd.output().unwrap()
+    };
+
+    let line = format!("Vcf line {} can't be parse", header.len() + 1);
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&line));
+
+    for args in [
+        &["--bad-record", "skip"][..],
+        &["--bad-record", "placeholder"],
+        &["--bad-record", "placeholder", "--stream"],
+    ] {
+        let output = run(args);
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(&line));
+        assert!(stderr.contains("1 vcf records can't be parse"));
+
+        if cfg!(feature = "json") {
+            let myth = std::fs::read_to_string(&output_path)?;
+            assert_eq!(
+                myth.contains("InvalidRecord"),
+                args.contains(&"placeholder")
+            );
+        }
+    }
+
+    Ok(())
+}
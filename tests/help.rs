@@ -8,7 +8,7 @@
 
 const USAGE: &[u8] = b"A variant annotater
 
-Usage: variant_myth [OPTIONS] --input <VARIANT_PATHS> --reference <REFERENCE_PATH> --annotations <ANNOTATIONS_PATH> <COMMAND>
+Usage: variant_myth [OPTIONS] --reference <REFERENCE_PATH> --annotations <ANNOTATIONS_PATH> <COMMAND>
 
 Commands:
 ";
@@ -19,6 +19,12 @@ const SUBCOMMAND_PARQUET: &[u8] = b"  parquet  Output are write in parquet forma
 #[cfg(feature = "json")]
 const SUBCOMMAND_JSON: &[u8] = b"  json     Output are write in json format
 ";
+#[cfg(feature = "vcf")]
+const SUBCOMMAND_VCF: &[u8] = b"  vcf      Output are write in vcf format
+";
+const SUBCOMMAND_INDEX: &[u8] =
+    b"  index    Write annotations and reference in a cache, variants aren't annotate
+";
 const SUBCOMMAND_HELP: &[u8] =
     b"  help     Print this message or the help of the given subcommand(s)
 
@@ -26,17 +32,49 @@ const SUBCOMMAND_HELP: &[u8] =
 
 const LOCAL_OPTIONS: &[u8] = b"Options:
   -i, --input <VARIANT_PATHS>
-          Variants path
+          Variants path, vcf or bcf, `-` read stdin, required except by index subcommand
+      --region <REGIONS>
+          Only annotate region chr:start-end, or chr, chr:pos, chr:start-, input need an index
+      --regions-file <REGIONS_PATH>
+          Only annotate regions of bed file, input need an index
   -r, --reference <REFERENCE_PATH>
           Reference genome path
+      --indexed-reference
+          Read reference on demand with fai index, and gzi index if bgzip compressed
+      --reference-cache <REFERENCE_CACHE>
+          Number of reference blocks, of 65,536 bases, keep in memory with --indexed-reference [default: 1024]
   -a, --annotations <ANNOTATIONS_PATH>
-          Annotation path
+          Annotation path, gff3 or gtf
+      --cache <CACHE_PATH>
+          Cache build by index subcommand, read in place of annotations and reference
+      --verify-cache
+          Check checksum of cache, annotations and reference with --cache, all files are read
+      --stream
+          Load annotations and reference one chromosome at a time, variants must be group by chromosome
   -t, --translate <TRANSLATE_PATH>
           Translate table path, if not set use human
   -d, --updown-distance <UPDOWN_DISTANCE>
           [Up|Down]stream transcript distance, default: 5,000
   -c, --annotators-choices <ANNOTATORS_CHOICES>
           Select which type of annotation you want run [possible values: gene, feature, effect, hgvs]
+      --splice-site <SPLICE_SITE>
+          Number of intronic bases of splice donor and acceptor site [default: 2]
+      --splice-region-exon <SPLICE_REGION_EXON>
+          Number of exonic bases of splice region [default: 3]
+      --splice-region-intron <SPLICE_REGION_INTRON>
+          Number of intronic bases of splice region [default: 8]
+      --no-normalize
+          Disable trim and left alignment of variant against reference
+      --ref-mismatch <REF_MISMATCH>
+          What to do with variant where REF didn't match reference genome [default: warn] [possible values: warn, flag, skip]
+      --bad-record <BAD_RECORD>
+          What to do with vcf record that can't be parse [default: fail] [possible values: fail, skip, placeholder]
+      --transcript-selection <TRANSCRIPT_SELECTION>
+          Which transcripts are report [default: all] [possible values: all, most-severe, canonical, allowlist]
+      --transcript-allowlist <TRANSCRIPT_ALLOWLIST>
+          File with one transcript id by line, required by allowlist transcript selection
+      --attributes <ATTRIBUTES>
+          Attribute keys of transcript, or gene, copy in annotations (comma separated)
 ";
 
 #[cfg(feature = "parallel")]
@@ -70,7 +108,10 @@ fn help_message() -> anyhow::Result<()> {
     help.extend(SUBCOMMAND_PARQUET);
     #[cfg(feature = "json")]
     help.extend(SUBCOMMAND_JSON);
+    #[cfg(feature = "vcf")]
+    help.extend(SUBCOMMAND_VCF);
 
+    help.extend(SUBCOMMAND_INDEX);
     help.extend(SUBCOMMAND_HELP);
     help.extend(LOCAL_OPTIONS);
 